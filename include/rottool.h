@@ -0,0 +1,131 @@
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#ifndef ROTTOOL_H
+#define ROTTOOL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum RottoolStatus {
+  ROTTOOL_STATUS_OK = 0,
+  ROTTOOL_STATUS_NULL_POINTER = 1,
+  // Wrong number of values, a zero quaternion or axis, or text that does not parse.
+  ROTTOOL_STATUS_INVALID_ARGUMENT = 2,
+  // The output buffer is too short; the needed length was still reported where possible.
+  ROTTOOL_STATUS_BUFFER_TOO_SMALL = 3,
+} RottoolStatus;
+
+// Flat number layouts for [`rottool_convert`]. The functions take these as `uint32_t`, so a
+// value from C is checked rather than trusted to be a variant.
+typedef enum RottoolRepresentation {
+  ROTTOOL_REPRESENTATION_QUATERNION_WXYZ = 0,
+  ROTTOOL_REPRESENTATION_QUATERNION_XYZW = 1,
+  // Angle in radians, then the axis.
+  ROTTOOL_REPRESENTATION_ANGLE_AXIS = 2,
+  // Axis times angle in radians.
+  ROTTOOL_REPRESENTATION_ROTATION_VECTOR = 3,
+  ROTTOOL_REPRESENTATION_MRP = 4,
+  ROTTOOL_REPRESENTATION_GIBBS = 5,
+  // Three angles in radians, in the given sequence and frame.
+  ROTTOOL_REPRESENTATION_EULER = 6,
+  ROTTOOL_REPRESENTATION_ROTATION_MATRIX_ROW_MAJOR = 7,
+  ROTTOOL_REPRESENTATION_ROTATION_MATRIX_COLUMN_MAJOR = 8,
+} RottoolRepresentation;
+
+// Text layouts for [`rottool_parse`] and [`rottool_format`], as in the raw string box.
+typedef enum RottoolLayout {
+  ROTTOOL_LAYOUT_COLUMN_MAJOR4X4 = 0,
+  ROTTOOL_LAYOUT_ROW_MAJOR4X4 = 1,
+  ROTTOOL_LAYOUT_COLUMN_MAJOR3X3 = 2,
+  ROTTOOL_LAYOUT_ROW_MAJOR3X3 = 3,
+  ROTTOOL_LAYOUT_QUATERNION_WXYZ = 4,
+  ROTTOOL_LAYOUT_QUATERNION_XYZW = 5,
+  ROTTOOL_LAYOUT_CONTINUOUS6D = 6,
+  ROTTOOL_LAYOUT_SVD9D = 7,
+  ROTTOOL_LAYOUT_DUAL_QUATERNION_WXYZ = 8,
+  ROTTOOL_LAYOUT_DUAL_QUATERNION_XYZW = 9,
+  ROTTOOL_LAYOUT_TF2_ECHO = 10,
+  ROTTOOL_LAYOUT_ROS_YAML = 11,
+  ROTTOOL_LAYOUT_ROTATION_VECTOR = 12,
+  ROTTOOL_LAYOUT_ROLL_PITCH_YAW = 13,
+} RottoolLayout;
+
+typedef enum RottoolEulerSequence {
+  ROTTOOL_EULER_SEQUENCE_XYZ = 0,
+  ROTTOOL_EULER_SEQUENCE_XZY = 1,
+  ROTTOOL_EULER_SEQUENCE_YXZ = 2,
+  ROTTOOL_EULER_SEQUENCE_YZX = 3,
+  ROTTOOL_EULER_SEQUENCE_ZXY = 4,
+  ROTTOOL_EULER_SEQUENCE_ZYX = 5,
+  ROTTOOL_EULER_SEQUENCE_XYX = 6,
+  ROTTOOL_EULER_SEQUENCE_XZX = 7,
+  ROTTOOL_EULER_SEQUENCE_YXY = 8,
+  ROTTOOL_EULER_SEQUENCE_YZY = 9,
+  ROTTOOL_EULER_SEQUENCE_ZXZ = 10,
+  ROTTOOL_EULER_SEQUENCE_ZYZ = 11,
+} RottoolEulerSequence;
+
+typedef enum RottoolEulerFrame {
+  ROTTOOL_EULER_FRAME_INTRINSIC = 0,
+  ROTTOOL_EULER_FRAME_EXTRINSIC = 1,
+} RottoolEulerFrame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// How many numbers one rotation takes in `representation`, a [`Representation`], or 0 when it
+// is not one.
+size_t rottool_number_count(uint32_t representation);
+
+// Converts `values_len` numbers in `from` into `to`, both a [`Representation`], writing at most
+// `out_len` numbers to `out`. Euler angles are read and written in `sequence`, an
+// [`EulerSequence`], and `frame`, an [`EulerFrame`].
+//
+// # Safety
+// `values` must point to `values_len` doubles and `out` to `out_len` doubles.
+enum RottoolStatus rottool_convert(uint32_t from,
+                                   const double *values,
+                                   size_t values_len,
+                                   uint32_t to,
+                                   double *out,
+                                   size_t out_len,
+                                   uint32_t sequence,
+                                   uint32_t frame);
+
+// Reads one rotation from the NUL-terminated `text` in `layout`, a [`Layout`], into `quat_wxyz`
+// (4 doubles). `translation` (3 doubles) and `has_translation` may be null; the translation is
+// zero for layouts without one.
+//
+// # Safety
+// `text` must be NUL-terminated, and the other pointers null or valid for their lengths.
+enum RottoolStatus rottool_parse(const char *text,
+                                 uint32_t layout,
+                                 double *quat_wxyz,
+                                 double *translation,
+                                 bool *has_translation);
+
+// Formats `quat_wxyz` (4 doubles) and `translation` (3 doubles, or null for zero) as text in
+// `layout`, a [`Layout`]. Writes the text and a NUL to `buffer` when it holds `capacity` bytes,
+// and its length without the NUL to `length` when that is not null. Call with a null buffer
+// and zero capacity to ask for the length.
+//
+// # Safety
+// `buffer` must be null or valid for `capacity` bytes, and the other pointers null or valid
+// for their lengths.
+enum RottoolStatus rottool_format(uint32_t layout,
+                                  const double *quat_wxyz,
+                                  const double *translation,
+                                  char *buffer,
+                                  size_t capacity,
+                                  size_t *length);
+
+// The message of the last failure on this thread, valid until the next failure on it.
+const char *rottool_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ROTTOOL_H */
@@ -0,0 +1,418 @@
+//! Best-fit rotation between corresponding point sets or direction sets.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+/// Newton iterations for the largest eigenvalue in [`quest`].
+const QUEST_ITERATIONS: usize = 20;
+/// Largest Gibbs vector norm accepted from QUEST before falling back to the q-method (about 179.9°).
+const QUEST_MAX_GIBBS_NORM: f64 = 1e3;
+/// Smallest source point variance [`umeyama`] estimates a scale from.
+const MIN_SOURCE_VARIANCE: f64 = 1e-12;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum AlignMode {
+    /// Corresponding 3D points, solved with Kabsch/Umeyama.
+    Points,
+    /// Weighted unit directions, solved as Wahba's problem with QUEST.
+    Directions,
+}
+
+/// `target ≈ scale * rotation * source + translation`.
+#[derive(Debug, Clone, Copy)]
+pub struct Similarity {
+    pub rotation: na::UnitQuaternion<f64>,
+    pub translation: na::Vector3<f64>,
+    pub scale: f64,
+}
+
+/// Reads consecutive triples of numbers as 3D vectors.
+///
+/// # Errors
+/// When the number count is not a multiple of three.
+pub fn parse_vectors(text: &str) -> anyhow::Result<Vec<na::Vector3<f64>>> {
+    let nums = crate::split_numbers(text);
+    if !nums.len().is_multiple_of(3) {
+        anyhow::bail!("{} numbers is not a multiple of 3", nums.len());
+    }
+    Ok(nums
+        .chunks_exact(3)
+        .map(na::Vector3::from_column_slice)
+        .collect())
+}
+
+/// Kabsch (rotation only, optionally with translation) and Umeyama (with scale) alignment.
+///
+/// # Errors
+/// When the sets differ in length or are empty, or a scale is requested for coincident source
+/// points.
+pub fn umeyama(
+    source: &[na::Vector3<f64>],
+    target: &[na::Vector3<f64>],
+    with_translation: bool,
+    with_scale: bool,
+) -> anyhow::Result<Similarity> {
+    if source.len() != target.len() || source.is_empty() {
+        anyhow::bail!(
+            "need the same nonzero number of points, got {} and {}",
+            source.len(),
+            target.len()
+        );
+    }
+    let count = source.len() as f64;
+    let centroid = |points: &[na::Vector3<f64>]| {
+        if with_translation {
+            points.iter().sum::<na::Vector3<f64>>() / count
+        } else {
+            na::Vector3::zeros()
+        }
+    };
+    let (source_mean, target_mean) = (centroid(source), centroid(target));
+    let covariance = source
+        .iter()
+        .zip(target)
+        .fold(na::Matrix3::zeros(), |acc, (s, t)| {
+            acc + (t - target_mean) * (s - source_mean).transpose()
+        })
+        / count;
+    let svd = covariance.svd(true, true);
+    let u = svd.u.expect("u was requested");
+    let v_t = svd.v_t.expect("v_t was requested");
+    let reflection = na::Vector3::new(1.0, 1.0, (u * v_t).determinant().signum());
+    let rotation =
+        na::Rotation3::from_matrix_unchecked(u * na::Matrix3::from_diagonal(&reflection) * v_t);
+    let scale = if with_scale {
+        let source_variance = source
+            .iter()
+            .map(|s| (s - source_mean).norm_squared())
+            .sum::<f64>()
+            / count;
+        if source_variance < MIN_SOURCE_VARIANCE {
+            anyhow::bail!("the source points coincide, so the scale is undetermined");
+        }
+        svd.singular_values.dot(&reflection) / source_variance
+    } else {
+        1.0
+    };
+    Ok(Similarity {
+        rotation: na::UnitQuaternion::from_rotation_matrix(&rotation),
+        translation: target_mean - scale * (rotation * source_mean),
+        scale,
+    })
+}
+
+/// Solves Wahba's problem `min Σ wᵢ |targetᵢ - R sourceᵢ|²` for unit directions with QUEST,
+/// falling back to Davenport's q-method near the 180° singularity of the Gibbs vector.
+///
+/// # Errors
+/// When the sets differ in length or are empty, or a direction is zero.
+pub fn quest(
+    source: &[na::Vector3<f64>],
+    target: &[na::Vector3<f64>],
+    weights: &[f64],
+) -> anyhow::Result<na::UnitQuaternion<f64>> {
+    if source.len() != target.len() || source.len() != weights.len() || source.is_empty() {
+        anyhow::bail!(
+            "need the same nonzero number of directions and weights, got {}, {} and {}",
+            source.len(),
+            target.len(),
+            weights.len()
+        );
+    }
+    // Attitude profile matrix B = Σ wᵢ bᵢ rᵢᵀ, with b the observed (target) directions.
+    let mut profile = na::Matrix3::zeros();
+    for ((s, t), weight) in source.iter().zip(target).zip(weights) {
+        let (Some(s), Some(t)) = (s.try_normalize(0.0), t.try_normalize(0.0)) else {
+            anyhow::bail!("directions must be nonzero");
+        };
+        profile += t * s.transpose() * *weight;
+    }
+    let sigma = profile.trace();
+    let symmetric = profile + profile.transpose();
+    let z = na::Vector3::new(
+        profile.m23 - profile.m32,
+        profile.m31 - profile.m13,
+        profile.m12 - profile.m21,
+    );
+
+    // Largest root of the characteristic polynomial of Davenport's K, by Newton from Σ wᵢ.
+    let kappa = adjugate_trace(&symmetric);
+    let delta = symmetric.determinant();
+    let a = sigma * sigma - kappa;
+    let b = sigma * sigma + z.norm_squared();
+    let c = delta + z.dot(&(symmetric * z));
+    let d = z.dot(&(symmetric * symmetric * z));
+    let mut lambda = weights.iter().sum::<f64>();
+    for _ in 0..QUEST_ITERATIONS {
+        let f = lambda.powi(4) - (a + b) * lambda.powi(2) - c * lambda + (a * b + c * sigma - d);
+        let df = 4.0 * lambda.powi(3) - 2.0 * (a + b) * lambda - c;
+        if df == 0.0 {
+            break;
+        }
+        lambda -= f / df;
+    }
+
+    let gibbs = ((lambda + sigma) * na::Matrix3::identity() - symmetric)
+        .try_inverse()
+        .map(|inverse| inverse * z)
+        .filter(|gibbs| gibbs.norm() < QUEST_MAX_GIBBS_NORM);
+    // Shuster's quaternion describes the passive attitude matrix, hence the conjugate.
+    Ok(gibbs.map_or_else(
+        || davenport(&symmetric, &z, sigma),
+        |gibbs| crate::convert::gibbs_to_quat(&-gibbs),
+    ))
+}
+
+/// Davenport's q-method: the dominant eigenvector of `K = [[S - σI, z], [zᵀ, σ]]`.
+fn davenport(
+    symmetric: &na::Matrix3<f64>,
+    z: &na::Vector3<f64>,
+    sigma: f64,
+) -> na::UnitQuaternion<f64> {
+    let mut davenport = na::Matrix4::zeros();
+    davenport
+        .fixed_view_mut::<3, 3>(0, 0)
+        .copy_from(&(symmetric - sigma * na::Matrix3::identity()));
+    davenport.fixed_view_mut::<3, 1>(0, 3).copy_from(z);
+    davenport
+        .fixed_view_mut::<1, 3>(3, 0)
+        .copy_from(&z.transpose());
+    davenport.m44 = sigma;
+    let eigen = davenport.symmetric_eigen();
+    let q = eigen
+        .eigenvectors
+        .column(eigen.eigenvalues.imax())
+        .into_owned();
+    na::UnitQuaternion::from_quaternion(na::Quaternion::new(q.w, -q.x, -q.y, -q.z))
+}
+
+/// `tr(adj S)`, the sum of the principal 2x2 minors.
+fn adjugate_trace(m: &na::Matrix3<f64>) -> f64 {
+    (m.m22 * m.m33 - m.m23 * m.m32)
+        + (m.m11 * m.m33 - m.m13 * m.m31)
+        + (m.m11 * m.m22 - m.m12 * m.m21)
+}
+
+struct Solution {
+    similarity: Similarity,
+    /// Distance per point, or angle in degrees per direction.
+    residuals: Vec<f64>,
+}
+
+/// State of the point-set alignment panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AlignTool {
+    mode: AlignMode,
+    source: String,
+    target: String,
+    weights: String,
+    with_translation: bool,
+    with_scale: bool,
+    #[serde(skip)]
+    solution: Option<anyhow::Result<Solution>>,
+}
+
+impl Default for AlignTool {
+    fn default() -> Self {
+        Self {
+            mode: AlignMode::Points,
+            source: String::new(),
+            target: String::new(),
+            weights: String::new(),
+            with_translation: true,
+            with_scale: false,
+            solution: None,
+        }
+    }
+}
+
+impl AlignTool {
+    /// Returns the solved rotation and translation the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::Isometry3<f64>> {
+        ui.horizontal(|ui| {
+            if ui.button("solve").clicked() {
+                self.solution = Some(self.solve());
+            }
+            egui::ComboBox::from_label("input")
+                .selected_text(format!("{:?}", self.mode))
+                .show_ui(ui, |ui| {
+                    for mode in AlignMode::iter() {
+                        ui.selectable_value(&mut self.mode, mode, format!("{mode:?}"));
+                    }
+                });
+            if self.mode == AlignMode::Points {
+                ui.checkbox(&mut self.with_translation, "translation");
+                ui.checkbox(&mut self.with_scale, "scale");
+            }
+        });
+        ui.label("Finds R (and t, s) so that target ≈ s·R·source + t, one x y z triple per row.");
+        ui.columns(2, |columns| {
+            if let [source_ui, target_ui] = columns {
+                source_ui.label("Source");
+                source_ui.add(
+                    egui::TextEdit::multiline(&mut self.source)
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(6),
+                );
+                target_ui.label("Target");
+                target_ui.add(
+                    egui::TextEdit::multiline(&mut self.target)
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(6),
+                );
+            }
+        });
+        if self.mode == AlignMode::Directions {
+            ui.horizontal(|ui| {
+                ui.label("Weights");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.weights)
+                        .hint_text("one per direction, all 1 if empty"),
+                );
+            });
+        }
+        self.solution_ui(ui)
+    }
+
+    fn solution_ui(&self, ui: &mut egui::Ui) -> Option<na::Isometry3<f64>> {
+        let solution = match &self.solution {
+            Some(Ok(solution)) => solution,
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+            None => return None,
+        };
+        let Similarity {
+            rotation,
+            translation,
+            scale,
+        } = solution.similarity;
+        let mut loaded = None;
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Rotation: [{:.4}, {:.4}, {:.4}, {:.4}]  translation: [{:.4}, {:.4}, {:.4}]  scale: {scale:.6}",
+                rotation.w, rotation.i, rotation.j, rotation.k, translation.x, translation.y, translation.z
+            ));
+            if ui.small_button("load").clicked() {
+                loaded = Some(na::Isometry3::from_parts(translation.into(), rotation));
+            }
+        });
+        let unit = match self.mode {
+            AlignMode::Points => "",
+            AlignMode::Directions => "°",
+        };
+        let rms = (solution.residuals.iter().map(|r| r * r).sum::<f64>()
+            / solution.residuals.len().max(1) as f64)
+            .sqrt();
+        ui.label(format!("Residuals (RMS {rms:.6}{unit}):"));
+        egui::ScrollArea::vertical()
+            .max_height(120.0)
+            .id_salt("align residuals")
+            .show(ui, |ui| {
+                for (i, residual) in solution.residuals.iter().enumerate() {
+                    ui.label(format!("#{i}: {residual:.6}{unit}"));
+                }
+            });
+        loaded
+    }
+
+    fn solve(&self) -> anyhow::Result<Solution> {
+        let source = parse_vectors(&self.source)?;
+        let target = parse_vectors(&self.target)?;
+        match self.mode {
+            AlignMode::Points => {
+                let similarity = umeyama(&source, &target, self.with_translation, self.with_scale)?;
+                let residuals = source
+                    .iter()
+                    .zip(&target)
+                    .map(|(s, t)| {
+                        (similarity.scale * (similarity.rotation * s) + similarity.translation - t)
+                            .norm()
+                    })
+                    .collect();
+                Ok(Solution {
+                    similarity,
+                    residuals,
+                })
+            }
+            AlignMode::Directions => {
+                let mut weights = crate::split_numbers(&self.weights);
+                if weights.is_empty() {
+                    weights = vec![1.0; source.len()];
+                }
+                let rotation = quest(&source, &target, &weights)?;
+                let residuals = source
+                    .iter()
+                    .zip(&target)
+                    .map(|(s, t)| (rotation * s).angle(t).to_degrees())
+                    .collect();
+                Ok(Solution {
+                    similarity: Similarity {
+                        rotation,
+                        translation: na::Vector3::zeros(),
+                        scale: 1.0,
+                    },
+                    residuals,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_points() -> Vec<na::Vector3<f64>> {
+        vec![
+            na::Vector3::new(1.0, 0.0, 0.0),
+            na::Vector3::new(0.0, 2.0, 0.0),
+            na::Vector3::new(0.0, 0.0, 3.0),
+            na::Vector3::new(1.0, -1.0, 0.5),
+        ]
+    }
+
+    #[test]
+    fn test_umeyama_recovers_similarity() {
+        let rotation = na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5);
+        let translation = na::Vector3::new(0.5, -2.0, 1.0);
+        let source = sample_points();
+        let target = source
+            .iter()
+            .map(|s| 1.5 * (rotation * s) + translation)
+            .collect::<Vec<_>>();
+        let solved = umeyama(&source, &target, true, true).expect("valid input");
+        assert!(solved.rotation.angle_to(&rotation) < 1e-9, "rotation");
+        assert!(
+            (solved.translation - translation).norm() < 1e-9,
+            "translation"
+        );
+        assert!((solved.scale - 1.5).abs() < 1e-9, "scale");
+        let coincident = vec![na::Vector3::new(1.0, 2.0, 3.0); target.len()];
+        assert!(
+            umeyama(&coincident, &target, true, true).is_err(),
+            "no scale from coincident points"
+        );
+    }
+
+    #[test]
+    fn test_quest_matches_rotation() {
+        let source = sample_points();
+        let weights = vec![1.0, 0.5, 2.0, 1.0];
+        for rotation in [
+            na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5),
+            na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), std::f64::consts::PI),
+        ] {
+            let target = source.iter().map(|s| rotation * s).collect::<Vec<_>>();
+            let solved = quest(&source, &target, &weights).expect("valid input");
+            assert!(
+                solved.angle_to(&rotation) < 1e-9,
+                "QUEST recovers {rotation}"
+            );
+        }
+    }
+}
@@ -1,23 +1,31 @@
 use eframe::Frame;
 use nalgebra as na;
-use strum::IntoEnumIterator;
+use strum::IntoEnumIterator as _;
+
+use crate::euler::{EulerFrame, EulerSequence};
+use crate::raw::RawStringType;
+
+/// `|σ|` above which the MRP view warns about the upcoming shadow set switch (about 168°).
+const MRP_SWITCH_WARNING: f64 = 0.9;
+/// Rotation angle above which the Gibbs view warns about the 180° singularity.
+const GIBBS_SINGULARITY_WARNING: f64 = 170.0 * std::f64::consts::PI / 180.0;
+/// Distance from gimbal lock in degrees below which the Euler view warns.
+const EULER_LOCK_WARNING: f64 = 10.0;
 
 enum RotationRepr {
     Quaternion,
     AngleAxis,
+    Mrp,
+    Gibbs,
+    Euler,
     RotationMatrix,
     RawString,
-}
-#[derive(
-    Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
-)]
-enum RawStringType {
-    ColumnMajor4x4,
-    RowMajor4x4,
-    ColumnMajor3x3,
-    RowMajor3x3,
-    QuaternionWXYZ,
-    QuaternionXYZW,
+    Translation,
+    DualQuaternion,
+    /// A rotation produced by one of the tools.
+    Computed(na::UnitQuaternion<f64>),
+    /// A rotation and translation produced by one of the tools.
+    Transform(na::Isometry3<f64>),
 }
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -27,9 +35,40 @@ pub struct TemplateApp {
     // Example stuff:
     quat: [(String, String); 4],
     angleaxis: [(String, String); 4],
+    mrp: [(String, String); 3],
+    gibbs: [(String, String); 3],
+    euler: [(String, String); 3],
+    euler_sequence: EulerSequence,
+    euler_frame: EulerFrame,
+    euler_degrees: bool,
     rot_matrix: [String; 9],
+    translation: [(String, String); 3],
+    dual_quat: [(String, String); 8],
     raw_string: String,
     raw_string_type: RawStringType,
+    /// Detect the layout on import instead of using `raw_string_type` as picked.
+    raw_string_auto: bool,
+    #[serde(skip)]
+    raw_string_residual: Option<f64>,
+    /// Layouts considered by the last auto-detecting import.
+    #[serde(skip)]
+    raw_string_detections: Option<Vec<crate::raw::Detection>>,
+    rotation: na::UnitQuaternion<f64>,
+    twist_axis: [(String, String); 3],
+    /// Show closed forms such as `√2/2` next to the decimals.
+    exact: bool,
+    interpolation: crate::interp::InterpolationTool,
+    average: crate::average::AverageTool,
+    align: crate::align::AlignTool,
+    construct: crate::construct::ConstructTool,
+    triad: crate::triad::TriadTool,
+    kinematics: crate::kinematics::KinematicsTool,
+    sclerp: crate::dual::SclerpTool,
+    dh: crate::dh::DhTool,
+    urdf: crate::urdf::UrdfTool,
+    gltf: crate::gltf::GltfTool,
+    trajectory: crate::trajectory::TrajectoryTool,
+    structured: crate::structured::StructuredTool,
     edited: bool,
     footer_height: f32,
 }
@@ -49,6 +88,20 @@ impl Default for TemplateApp {
                 ("AxisY".to_owned(), "0.0".to_owned()),
                 ("AxisZ".to_owned(), "0.0".to_owned()),
             ],
+            mrp: [
+                ("σx".to_owned(), "0.0".to_owned()),
+                ("σy".to_owned(), "0.0".to_owned()),
+                ("σz".to_owned(), "0.0".to_owned()),
+            ],
+            gibbs: [
+                ("gx".to_owned(), "0.0".to_owned()),
+                ("gy".to_owned(), "0.0".to_owned()),
+                ("gz".to_owned(), "0.0".to_owned()),
+            ],
+            euler: euler_fields(EulerSequence::ZYX, true, [0.0; 3]),
+            euler_sequence: EulerSequence::ZYX,
+            euler_frame: EulerFrame::Intrinsic,
+            euler_degrees: true,
             rot_matrix: [
                 "1.0".to_owned(),
                 "0.0".to_owned(),
@@ -60,8 +113,45 @@ impl Default for TemplateApp {
                 "0.0".to_owned(),
                 "1.0".to_owned(),
             ],
+            translation: [
+                ("X".to_owned(), "0.0".to_owned()),
+                ("Y".to_owned(), "0.0".to_owned()),
+                ("Z".to_owned(), "0.0".to_owned()),
+            ],
+            dual_quat: [
+                ("Rw".to_owned(), "1.0".to_owned()),
+                ("Rx".to_owned(), "0.0".to_owned()),
+                ("Ry".to_owned(), "0.0".to_owned()),
+                ("Rz".to_owned(), "0.0".to_owned()),
+                ("Dw".to_owned(), "0.0".to_owned()),
+                ("Dx".to_owned(), "0.0".to_owned()),
+                ("Dy".to_owned(), "0.0".to_owned()),
+                ("Dz".to_owned(), "0.0".to_owned()),
+            ],
             raw_string: String::new(),
             raw_string_type: RawStringType::ColumnMajor4x4,
+            raw_string_auto: false,
+            raw_string_residual: None,
+            raw_string_detections: None,
+            rotation: na::UnitQuaternion::identity(),
+            twist_axis: [
+                ("AxisX".to_owned(), "0.0".to_owned()),
+                ("AxisY".to_owned(), "0.0".to_owned()),
+                ("AxisZ".to_owned(), "1.0".to_owned()),
+            ],
+            exact: false,
+            interpolation: Default::default(),
+            average: Default::default(),
+            align: Default::default(),
+            construct: Default::default(),
+            triad: Default::default(),
+            kinematics: Default::default(),
+            sclerp: Default::default(),
+            dh: Default::default(),
+            urdf: Default::default(),
+            gltf: Default::default(),
+            trajectory: Default::default(),
+            structured: Default::default(),
             edited: false,
             footer_height: 0.0,
         }
@@ -84,127 +174,124 @@ impl TemplateApp {
     }
 
     fn update_input(&mut self, edited_item: &RotationRepr) -> anyhow::Result<()> {
+        let mut translation = None;
         let quat = match edited_item {
             RotationRepr::Quaternion => {
                 na::UnitQuaternion::<f64>::from_quaternion(na::Quaternion::new(
-                    self.quat[0].1.parse()?,
-                    self.quat[1].1.parse()?,
-                    self.quat[2].1.parse()?,
-                    self.quat[3].1.parse()?,
+                    crate::expr::eval(&self.quat[0].1)?,
+                    crate::expr::eval(&self.quat[1].1)?,
+                    crate::expr::eval(&self.quat[2].1)?,
+                    crate::expr::eval(&self.quat[3].1)?,
                 ))
             }
             RotationRepr::AngleAxis => {
-                let angle = self.angleaxis[0].1.parse()?;
+                let angle = crate::expr::eval(&self.angleaxis[0].1)?;
                 let axis = na::UnitVector3::new_normalize(na::Vector3::new(
-                    self.angleaxis[1].1.parse()?,
-                    self.angleaxis[2].1.parse()?,
-                    self.angleaxis[3].1.parse()?,
+                    crate::expr::eval(&self.angleaxis[1].1)?,
+                    crate::expr::eval(&self.angleaxis[2].1)?,
+                    crate::expr::eval(&self.angleaxis[3].1)?,
                 ));
                 na::UnitQuaternion::from_axis_angle(&axis, angle)
             }
+            RotationRepr::Mrp => crate::convert::mrp_to_quat(&crate::parse_vector3(&self.mrp)?),
+            RotationRepr::Gibbs => {
+                crate::convert::gibbs_to_quat(&crate::parse_vector3(&self.gibbs)?)
+            }
+            RotationRepr::Euler => self.parse_euler()?,
             RotationRepr::RotationMatrix => {
-                let mut matrix = na::Matrix3::from_iterator(
-                    self.rot_matrix
-                        .iter()
-                        .map(|e| e.parse::<f64>().unwrap_or(0.0)),
-                );
+                let values = self
+                    .rot_matrix
+                    .iter()
+                    .map(|e| crate::expr::eval(e))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                let mut matrix = na::Matrix3::from_iterator(values);
                 if matrix.rank(0.0001) < 3 {
                     matrix = na::Matrix3::identity();
                 }
                 na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(&matrix))
             }
             RotationRepr::RawString => {
-                let nums = super::split_numbers(&self.raw_string);
-                match self.raw_string_type {
-                    RawStringType::ColumnMajor4x4 => {
-                        if nums.len() == 16 {
-                            let transform_mat = na::Matrix4::from_column_slice(&nums);
-                            let mut rot = na::Matrix3::identity();
-                            rot.copy_from(&transform_mat.fixed_view::<3, 3>(0, 0));
-                            na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
-                                &rot,
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                    RawStringType::RowMajor4x4 => {
-                        if nums.len() == 16 {
-                            let transform_mat = na::Matrix4::from_row_slice(&nums);
-                            let mut rot = na::Matrix3::identity();
-                            rot.copy_from(&transform_mat.fixed_view::<3, 3>(0, 0));
-                            na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
-                                &rot,
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                    RawStringType::ColumnMajor3x3 => {
-                        if nums.len() == 9 {
-                            na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
-                                &na::Matrix3::from_column_slice(&nums),
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                    RawStringType::RowMajor3x3 => {
-                        if nums.len() == 9 {
-                            na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
-                                &na::Matrix3::from_row_slice(&nums),
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                    RawStringType::QuaternionWXYZ => {
-                        if nums.len() == 4 {
-                            na::UnitQuaternion::from_quaternion(na::Quaternion::new(
-                                nums[0], nums[1], nums[2], nums[3],
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                    RawStringType::QuaternionXYZW => {
-                        if nums.len() == 4 {
-                            na::UnitQuaternion::from_quaternion(na::Quaternion::from_vector(
-                                na::Vector4::from_column_slice(&nums),
-                            ))
-                        } else {
-                            anyhow::bail!("len wrong");
-                        }
-                    }
-                }
+                let nums = self.raw_string_type.numbers(&self.raw_string)?;
+                let quat = self.raw_string_type.parse(&nums)?;
+                self.raw_string_residual = self.raw_string_type.residual(&nums, &quat);
+                translation = self.raw_string_type.translation(&nums);
+                quat
+            }
+            RotationRepr::Translation => {
+                translation = Some(crate::parse_vector3(&self.translation)?);
+                self.rotation
+            }
+            RotationRepr::DualQuaternion => {
+                let [rw, rx, ry, rz, dw, dx, dy, dz] = self
+                    .dual_quat
+                    .each_ref()
+                    .map(|dual_quat_e| crate::expr::eval(&dual_quat_e.1));
+                let transform =
+                    crate::dual::to_isometry([rw?, rx?, ry?, rz?], [dw?, dx?, dy?, dz?])?;
+                translation = Some(transform.translation.vector);
+                transform.rotation
+            }
+            RotationRepr::Computed(quat) => *quat,
+            RotationRepr::Transform(transform) => {
+                translation = Some(transform.translation.vector);
+                transform.rotation
             }
         };
-        self.quat[0].1 = format!("{:.4}", quat.w);
-        self.quat[1].1 = format!("{:.4}", quat.i);
-        self.quat[2].1 = format!("{:.4}", quat.j);
-        self.quat[3].1 = format!("{:.4}", quat.k);
-        if let Some(angleaxis) = quat.axis_angle() {
-            self.angleaxis[0].1 = format!("{:.4}", angleaxis.1);
-            self.angleaxis[1].1 = format!("{:.4}", angleaxis.0.x);
-            self.angleaxis[2].1 = format!("{:.4}", angleaxis.0.y);
-            self.angleaxis[3].1 = format!("{:.4}", angleaxis.0.z);
-        } else {
-            self.angleaxis[0].1 = format!("{:.4}", 0.0);
-            self.angleaxis[1].1 = format!("{:.4}", 1.0);
-            self.angleaxis[2].1 = format!("{:.4}", 0.0);
-            self.angleaxis[3].1 = format!("{:.4}", 0.0);
+        if let Some(translation) = translation {
+            for (translation_e, &x) in self.translation.iter_mut().zip(translation.iter()) {
+                crate::expr::set_field(&mut translation_e.1, x, false);
+            }
         }
-        quat.to_rotation_matrix()
-            .matrix()
-            .iter()
-            .enumerate()
-            .for_each(|(i, &x)| {
-                *self.rot_matrix.get_mut(i).expect("failed access") = format!("{x:.4}");
-            });
+        self.rotation = quat;
+        self.sync_views();
 
         Ok(())
     }
 
+    /// Writes the current rotation and translation into every view.
+    fn sync_views(&mut self) {
+        let quat = self.rotation;
+        for (quat_e, x) in self.quat.iter_mut().zip([quat.w, quat.i, quat.j, quat.k]) {
+            crate::expr::set_field(&mut quat_e.1, x, false);
+        }
+        let (axis, angle) = quat.axis_angle().unwrap_or((na::Vector3::x_axis(), 0.0));
+        for (angleaxis_e, x) in self
+            .angleaxis
+            .iter_mut()
+            .zip([angle, axis.x, axis.y, axis.z])
+        {
+            crate::expr::set_field(&mut angleaxis_e.1, x, false);
+        }
+        let mrp = crate::convert::quat_to_mrp(&quat);
+        for (mrp_e, &x) in self.mrp.iter_mut().zip(mrp.iter()) {
+            crate::expr::set_field(&mut mrp_e.1, x, false);
+        }
+        let gibbs = crate::convert::quat_to_gibbs(&quat);
+        for (gibbs_e, &x) in self.gibbs.iter_mut().zip(gibbs.iter()) {
+            crate::expr::set_field(&mut gibbs_e.1, x, false);
+        }
+        let euler = crate::euler::from_quat(self.euler_sequence, self.euler_frame, &quat);
+        let fields = euler_fields(self.euler_sequence, self.euler_degrees, euler.angles);
+        for ((euler_e, field), angle) in self.euler.iter_mut().zip(fields).zip(euler.angles) {
+            euler_e.0 = field.0;
+            let angle = if self.euler_degrees {
+                angle.to_degrees()
+            } else {
+                angle
+            };
+            crate::expr::set_field(&mut euler_e.1, angle, self.euler_degrees);
+        }
+        let matrix = quat.to_rotation_matrix();
+        for (rot_matrix_e, &x) in self.rot_matrix.iter_mut().zip(matrix.matrix().iter()) {
+            crate::expr::set_field(rot_matrix_e, x, false);
+        }
+        let (real, dual) =
+            crate::dual::wxyz(&crate::dual::from_isometry(&self.current_transform()));
+        for (dual_quat_e, &x) in self.dual_quat.iter_mut().zip(real.iter().chain(&dual)) {
+            crate::expr::set_field(&mut dual_quat_e.1, x, false);
+        }
+    }
+
     fn quaternion_view(
         &mut self,
         strip_builder: egui_extras::StripBuilder<'_>,
@@ -223,6 +310,10 @@ impl TemplateApp {
                             *edited_item = Some(RotationRepr::Quaternion);
                         }
                         self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &quat_e.1, false);
+                        if self.exact {
+                            crate::exact::label(ui, &quat_e.1, false);
+                        }
                     });
                 }
             });
@@ -236,7 +327,7 @@ impl TemplateApp {
         strip_builder
             .sizes(egui_extras::Size::remainder().at_least(60.0).at_most(100.0), 4)
             .horizontal(|mut strip| {
-                for angleaxis_e in &mut self.angleaxis {
+                for (i, angleaxis_e) in self.angleaxis.iter_mut().enumerate() {
                     strip.cell(|ui| {
                         ui.label(&angleaxis_e.0);
                         let text_input_res = ui.add(egui::TextEdit::singleline(&mut angleaxis_e.1));
@@ -246,11 +337,316 @@ impl TemplateApp {
                             *edited_item = Some(RotationRepr::AngleAxis);
                         }
                         self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &angleaxis_e.1, false);
+                        if self.exact {
+                            crate::exact::label(ui, &angleaxis_e.1, i == 0);
+                        }
                     });
                 }
             });
     }
 
+    fn mrp_view(
+        &mut self,
+        strip_builder: egui_extras::StripBuilder<'_>,
+        edited_item: &mut Option<RotationRepr>,
+    ) {
+        strip_builder
+            .sizes(
+                egui_extras::Size::remainder().at_least(60.0).at_most(100.0),
+                3,
+            )
+            .horizontal(|mut strip| {
+                for mrp_e in &mut self.mrp {
+                    strip.cell(|ui| {
+                        ui.label(&mrp_e.0);
+                        let text_input_res = ui.add(egui::TextEdit::singleline(&mut mrp_e.1));
+                        if text_input_res.lost_focus()
+                            && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                        {
+                            *edited_item = Some(RotationRepr::Mrp);
+                        }
+                        self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &mrp_e.1, false);
+                        if self.exact {
+                            crate::exact::label(ui, &mrp_e.1, false);
+                        }
+                    });
+                }
+            });
+    }
+
+    fn mrp_notes(&self, ui: &mut egui::Ui) {
+        let Ok(mrp) = crate::parse_vector3(&self.mrp) else {
+            return;
+        };
+        if let Some(shadow) = crate::convert::mrp_shadow(&mrp) {
+            ui.label(format!(
+                "Shadow σˢ: [{:.4}, {:.4}, {:.4}]",
+                shadow.x, shadow.y, shadow.z
+            ));
+        } else {
+            ui.label("Shadow σˢ: undefined (360°)");
+        }
+        let norm = mrp.norm();
+        if norm > 1.0 {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("|σ| = {norm:.4} > 1: long-rotation set, switch to the shadow set"),
+            );
+        } else if norm > MRP_SWITCH_WARNING {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("|σ| = {norm:.4}: close to the shadow switching surface |σ| = 1"),
+            );
+        }
+    }
+
+    fn gibbs_view(
+        &mut self,
+        strip_builder: egui_extras::StripBuilder<'_>,
+        edited_item: &mut Option<RotationRepr>,
+    ) {
+        strip_builder
+            .sizes(
+                egui_extras::Size::remainder().at_least(60.0).at_most(100.0),
+                3,
+            )
+            .horizontal(|mut strip| {
+                for gibbs_e in &mut self.gibbs {
+                    strip.cell(|ui| {
+                        ui.label(&gibbs_e.0);
+                        let text_input_res = ui.add(egui::TextEdit::singleline(&mut gibbs_e.1));
+                        if text_input_res.lost_focus()
+                            && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                        {
+                            *edited_item = Some(RotationRepr::Gibbs);
+                        }
+                        self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &gibbs_e.1, false);
+                        if self.exact {
+                            crate::exact::label(ui, &gibbs_e.1, false);
+                        }
+                    });
+                }
+            });
+    }
+
+    fn gibbs_notes(&self, ui: &mut egui::Ui) {
+        let Ok(gibbs) = crate::parse_vector3(&self.gibbs) else {
+            return;
+        };
+        // |g| = tan(angle / 2)
+        let angle = 2.0 * gibbs.norm().atan();
+        if !angle.is_finite() || angle > GIBBS_SINGULARITY_WARNING {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "Rotation angle {:.2}°: the Gibbs vector diverges at 180°",
+                    angle.to_degrees()
+                ),
+            );
+        }
+    }
+
+    /// Euler angles in radians, in sequence order, as typed in.
+    fn parse_euler(&self) -> anyhow::Result<na::UnitQuaternion<f64>> {
+        let mut angles = [0.0; 3];
+        for (angle, euler_e) in angles.iter_mut().zip(&self.euler) {
+            *angle = if self.euler_degrees {
+                crate::expr::eval_degrees(&euler_e.1)?.to_radians()
+            } else {
+                crate::expr::eval(&euler_e.1)?
+            };
+        }
+        Ok(crate::euler::to_quat(
+            self.euler_sequence,
+            self.euler_frame,
+            angles,
+        ))
+    }
+
+    fn euler_settings(&mut self, ui: &mut egui::Ui, edited_item: &mut Option<RotationRepr>) {
+        let before = (self.euler_sequence, self.euler_frame, self.euler_degrees);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("sequence")
+                .selected_text(format!("{:?}", self.euler_sequence))
+                .show_ui(ui, |ui| {
+                    for sequence in EulerSequence::iter() {
+                        ui.selectable_value(
+                            &mut self.euler_sequence,
+                            sequence,
+                            format!("{sequence:?}"),
+                        );
+                    }
+                });
+            egui::ComboBox::from_label("frame")
+                .selected_text(format!("{:?}", self.euler_frame))
+                .show_ui(ui, |ui| {
+                    for frame in EulerFrame::iter() {
+                        ui.selectable_value(&mut self.euler_frame, frame, format!("{frame:?}"));
+                    }
+                });
+            ui.checkbox(&mut self.euler_degrees, "degrees");
+        });
+        if before != (self.euler_sequence, self.euler_frame, self.euler_degrees) {
+            *edited_item = Some(RotationRepr::Computed(self.rotation));
+        }
+    }
+
+    fn euler_view(
+        &mut self,
+        strip_builder: egui_extras::StripBuilder<'_>,
+        edited_item: &mut Option<RotationRepr>,
+    ) {
+        strip_builder
+            .sizes(
+                egui_extras::Size::remainder().at_least(60.0).at_most(100.0),
+                3,
+            )
+            .horizontal(|mut strip| {
+                for euler_e in &mut self.euler {
+                    strip.cell(|ui| {
+                        ui.label(&euler_e.0);
+                        let text_input_res = ui.add(egui::TextEdit::singleline(&mut euler_e.1));
+                        if text_input_res.lost_focus()
+                            && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                        {
+                            *edited_item = Some(RotationRepr::Euler);
+                        }
+                        self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &euler_e.1, self.euler_degrees);
+                        if self.exact && !self.euler_degrees {
+                            crate::exact::label(ui, &euler_e.1, true);
+                        }
+                    });
+                }
+            });
+    }
+
+    fn euler_notes(&self, ui: &mut egui::Ui) {
+        let Ok(quat) = self.parse_euler() else {
+            return;
+        };
+        let solution = crate::euler::from_quat(self.euler_sequence, self.euler_frame, &quat);
+        let [first, _, third] = self.euler_sequence.axes().map(crate::euler::axis_name);
+        let distance = solution.lock_distance.to_degrees();
+        if solution.locked {
+            ui.label(
+                egui::RichText::new(format!(
+                    "Gimbal lock: the first ({first}) and third ({third}) axes coincide"
+                ))
+                .color(ui.visuals().warn_fg_color)
+                .strong()
+                .heading(),
+            );
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "Only their combined angle is determined; by convention the third angle ({third}) is fixed to 0 and the first carries the whole turn"
+                ),
+            );
+        } else if distance < EULER_LOCK_WARNING {
+            ui.label(
+                egui::RichText::new(format!(
+                    "Near gimbal lock: {distance:.3}° from the singular middle angle ({})",
+                    self.euler_sequence.singular_middle_angles()
+                ))
+                .color(ui.visuals().warn_fg_color)
+                .strong()
+                .heading(),
+            );
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "The split between the {first} and {third} angles is ill-conditioned: small changes of the rotation move them a lot"
+                ),
+            );
+        } else {
+            ui.label(format!(
+                "Distance to gimbal lock: {distance:.2}° (middle angle singular at {})",
+                self.euler_sequence.singular_middle_angles()
+            ));
+        }
+        let [(_, a), (_, b), (_, c)] =
+            euler_fields(self.euler_sequence, self.euler_degrees, solution.alternate);
+        let alternate = format!("[{a}, {b}, {c}]");
+        ui.horizontal(|ui| {
+            ui.label(format!("Alternate solution: {alternate}"));
+            if ui.small_button("copy").clicked() {
+                ui.ctx().copy_text(alternate.clone());
+            }
+        });
+    }
+
+    fn translation_view(
+        &mut self,
+        strip_builder: egui_extras::StripBuilder<'_>,
+        edited_item: &mut Option<RotationRepr>,
+    ) {
+        strip_builder
+            .sizes(
+                egui_extras::Size::remainder().at_least(60.0).at_most(100.0),
+                3,
+            )
+            .horizontal(|mut strip| {
+                for translation_e in &mut self.translation {
+                    strip.cell(|ui| {
+                        ui.label(&translation_e.0);
+                        let text_input_res =
+                            ui.add(egui::TextEdit::singleline(&mut translation_e.1));
+                        if text_input_res.lost_focus()
+                            && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                        {
+                            *edited_item = Some(RotationRepr::Translation);
+                        }
+                        self.edited = text_input_res.changed() || self.edited;
+                        crate::expr::hint(ui, &translation_e.1, false);
+                        if self.exact {
+                            crate::exact::label(ui, &translation_e.1, false);
+                        }
+                    });
+                }
+            });
+    }
+
+    /// The current rotation with the translation view's translation, zero if it does not parse.
+    fn current_transform(&self) -> na::Isometry3<f64> {
+        let translation =
+            crate::parse_vector3(&self.translation).unwrap_or_else(|_| na::Vector3::zeros());
+        na::Isometry3::from_parts(translation.into(), self.rotation)
+    }
+
+    /// Real part on the first row, dual part on the second.
+    fn dual_quaternion_view(&mut self, ui: &mut egui::Ui, edited_item: &mut Option<RotationRepr>) {
+        for part in self.dual_quat.chunks_mut(4) {
+            egui_extras::StripBuilder::new(ui)
+                .sizes(
+                    egui_extras::Size::remainder().at_least(60.0).at_most(100.0),
+                    4,
+                )
+                .horizontal(|mut strip| {
+                    for dual_quat_e in part {
+                        strip.cell(|ui| {
+                            ui.label(&dual_quat_e.0);
+                            let text_input_res =
+                                ui.add(egui::TextEdit::singleline(&mut dual_quat_e.1));
+                            if text_input_res.lost_focus()
+                                && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                            {
+                                *edited_item = Some(RotationRepr::DualQuaternion);
+                            }
+                            self.edited = text_input_res.changed() || self.edited;
+                            crate::expr::hint(ui, &dual_quat_e.1, false);
+                            if self.exact {
+                                crate::exact::label(ui, &dual_quat_e.1, false);
+                            }
+                        });
+                    }
+                });
+        }
+    }
+
     fn rotation_matrix_view(
         &mut self,
         strip_builder: egui_extras::StripBuilder<'_>,
@@ -273,18 +669,79 @@ impl TemplateApp {
                                 *edited_item = Some(RotationRepr::RotationMatrix);
                             }
                             self.edited = text_input_res.changed() || self.edited;
+                            if let Some(text) = self.rot_matrix.get(3 * col + row) {
+                                crate::expr::hint(ui, text, false);
+                                if self.exact {
+                                    crate::exact::label(ui, text, false);
+                                }
+                            }
                         }
                     });
                 }
             });
     }
 
+    /// The layouts auto-detection considered, to switch to an alternative.
+    fn detections_ui(&mut self, ui: &mut egui::Ui, edited_item: &mut Option<RotationRepr>) {
+        let Some(detections) = &self.raw_string_detections else {
+            return;
+        };
+        if detections.is_empty() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "No layout takes this many numbers; pick one by hand",
+            );
+            return;
+        }
+        let ambiguous = detections
+            .get(1)
+            .is_some_and(|second| second.confidence > crate::raw::AMBIGUOUS_CONFIDENCE);
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Detected:");
+            for detection in detections {
+                let text = format!(
+                    "{:?} {:.0}%",
+                    detection.layout,
+                    detection.confidence * 100.0
+                );
+                if ui
+                    .selectable_label(self.raw_string_type == detection.layout, text)
+                    .clicked()
+                {
+                    self.raw_string_type = detection.layout;
+                    *edited_item = Some(RotationRepr::RawString);
+                }
+            }
+        });
+        if ambiguous {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Ambiguous: the alternatives fit these numbers about as well; check the result",
+            );
+        }
+    }
+
     fn raw_string_access(&mut self, ui: &mut egui::Ui, edited_item: &mut Option<RotationRepr>) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
                 if ui.button("import").clicked() {
+                    if self.raw_string_auto {
+                        let detections = crate::raw::detect(&self.raw_string);
+                        if let Some(best) = detections.first() {
+                            self.raw_string_type = best.layout;
+                        }
+                        self.raw_string_detections = Some(detections);
+                    }
                     *edited_item = Some(RotationRepr::RawString);
                 }
+                if ui.button("export").clicked()
+                    && let Ok(translation) = crate::parse_vector3(&self.translation)
+                {
+                    self.raw_string = self
+                        .raw_string_type
+                        .format_transform(&self.rotation, &translation);
+                    self.raw_string_residual = None;
+                }
                 egui::ComboBox::from_label("type")
                     .selected_text(format!("{:?}", self.raw_string_type))
                     .show_ui(ui, |ui| {
@@ -292,11 +749,15 @@ impl TemplateApp {
                             ui.selectable_value(
                                 &mut self.raw_string_type,
                                 string_type,
-                                format!("{:?}", string_type),
+                                format!("{string_type:?}"),
                             );
                         }
-                    })
+                    });
+                if ui.checkbox(&mut self.raw_string_auto, "auto").changed() {
+                    self.raw_string_detections = None;
+                }
             });
+            self.detections_ui(ui, edited_item);
             let text_input_res = ui.add_sized(
                 [ui.available_size_before_wrap().x, 150.0],
                 egui::TextEdit::multiline(&mut self.raw_string).layouter(&mut |ui, text, _wrap_width| {
@@ -304,8 +765,210 @@ impl TemplateApp {
                 }),
             );
             self.edited = text_input_res.changed() || self.edited;
+            if let Some(residual) = self.raw_string_residual {
+                ui.label(format!(
+                    "Distance of the imported numbers from a valid rotation: {residual:.6}"
+                ));
+            }
+        });
+    }
+}
+
+impl TemplateApp {
+    /// The preview and one editable view per representation, in display order.
+    fn representation_views(
+        &mut self,
+        ui: &mut egui::Ui,
+        rotation_repr: &mut Option<RotationRepr>,
+    ) {
+        crate::preview::axes_preview(ui, &self.rotation, 160.0);
+        ui.checkbox(&mut self.exact, "exact values")
+            .on_hover_text("Show closed forms such as √2/2 or π/4 next to the decimals");
+        ui.separator();
+        ui.label(egui::RichText::new("Quaternion:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
+            self.quaternion_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+        });
+        ui.separator();
+        ui.label(egui::RichText::new("Angle-axis:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
+            self.angleaxis_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+        });
+        ui.separator();
+        ui.label(egui::RichText::new("Modified Rodrigues parameters:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout(
+            [ui.available_size_before_wrap().x, 0.0].into(),
+            egui::Layout::top_down(egui::Align::LEFT),
+            |ui| {
+                self.mrp_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+            },
+        );
+        self.mrp_notes(ui);
+        ui.separator();
+        ui.label(egui::RichText::new("Gibbs vector:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout(
+            [ui.available_size_before_wrap().x, 0.0].into(),
+            egui::Layout::top_down(egui::Align::LEFT),
+            |ui| {
+                self.gibbs_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+            },
+        );
+        self.gibbs_notes(ui);
+        ui.separator();
+        ui.label(egui::RichText::new("Euler angles:").heading());
+        self.euler_settings(ui, rotation_repr);
+        ui.separator();
+        ui.allocate_ui_with_layout(
+            [ui.available_size_before_wrap().x, 0.0].into(),
+            egui::Layout::top_down(egui::Align::LEFT),
+            |ui| {
+                self.euler_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+            },
+        );
+        self.euler_notes(ui);
+        ui.separator();
+        ui.label(egui::RichText::new("Rotation matrix:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
+            self.rotation_matrix_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+        });
+        ui.separator();
+        ui.label(egui::RichText::new("Translation:").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout(
+            [ui.available_size_before_wrap().x, 0.0].into(),
+            egui::Layout::top_down(egui::Align::LEFT),
+            |ui| {
+                self.translation_view(egui_extras::StripBuilder::new(ui), rotation_repr);
+            },
+        );
+        ui.separator();
+        ui.label(egui::RichText::new("Dual quaternion (d = ½ t r):").heading());
+        ui.separator();
+        ui.allocate_ui_with_layout(
+            [ui.available_size_before_wrap().x, 0.0].into(),
+            egui::Layout::top_down(egui::Align::LEFT),
+            |ui| {
+                self.dual_quaternion_view(ui, rotation_repr);
+            },
+        );
+    }
+
+    fn tools_ui(&mut self, ui: &mut egui::Ui, rotation_repr: &mut Option<RotationRepr>) {
+        egui::CollapsingHeader::new("Construct from vectors").show(ui, |ui| {
+            if let Some(quat) = self.construct.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Computed(quat));
+            }
+        });
+        egui::CollapsingHeader::new("TRIAD from accelerometer and magnetometer").show(ui, |ui| {
+            if let Some(quat) = self.triad.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Computed(quat));
+            }
+        });
+        egui::CollapsingHeader::new("Swing-twist decomposition")
+            .show(ui, |ui| self.swing_twist_tool(ui));
+        egui::CollapsingHeader::new("Angular velocity and rates").show(ui, |ui| {
+            self.kinematics.ui(
+                ui,
+                &self.rotation,
+                self.euler_sequence,
+                self.euler_frame,
+                self.euler_degrees,
+            );
+        });
+        egui::CollapsingHeader::new("Denavit-Hartenberg chain").show(ui, |ui| {
+            if let Some(transform) = self.dh.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
+        });
+        egui::CollapsingHeader::new("URDF forward kinematics").show(ui, |ui| {
+            if let Some(transform) = self.urdf.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
+        });
+        egui::CollapsingHeader::new("glTF nodes").show(ui, |ui| {
+            if let Some(transform) = self.gltf.ui(ui, &self.rotation) {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
+        });
+        egui::CollapsingHeader::new("Trajectory files").show(ui, |ui| {
+            if let Some(transform) = self
+                .trajectory
+                .ui(ui, self.euler_sequence, self.euler_frame)
+            {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
+        });
+        egui::CollapsingHeader::new("JSON, YAML and TOML import").show(ui, |ui| {
+            if let Some(candidate) = self.structured.ui(ui) {
+                *rotation_repr = Some(match candidate.translation {
+                    Some(translation) => RotationRepr::Transform(na::Isometry3::from_parts(
+                        translation.into(),
+                        candidate.rotation,
+                    )),
+                    None => RotationRepr::Computed(candidate.rotation),
+                });
+            }
+        });
+        egui::CollapsingHeader::new("Interpolation").show(ui, |ui| {
+            if let Some(quat) = self.interpolation.ui(ui, &self.rotation) {
+                *rotation_repr = Some(RotationRepr::Computed(quat));
+            }
+        });
+        egui::CollapsingHeader::new("ScLERP between poses").show(ui, |ui| {
+            if let Some(transform) = self.sclerp.ui(ui, &self.current_transform()) {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
+        });
+        egui::CollapsingHeader::new("Rotation averaging").show(ui, |ui| {
+            if let Some(quat) = self.average.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Computed(quat));
+            }
+        });
+        egui::CollapsingHeader::new("Point-set alignment").show(ui, |ui| {
+            if let Some(transform) = self.align.ui(ui) {
+                *rotation_repr = Some(RotationRepr::Transform(transform));
+            }
         });
     }
+
+    fn swing_twist_tool(&mut self, ui: &mut egui::Ui) {
+        ui.label("Twist axis:");
+        crate::vector3_input(ui, &mut self.twist_axis);
+        let Some(axis) = crate::parse_vector3(&self.twist_axis)
+            .ok()
+            .and_then(|axis| na::UnitVector3::try_new(axis, f64::EPSILON))
+        else {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "The twist axis must be a nonzero vector",
+            );
+            return;
+        };
+        let (swing, twist) = crate::convert::swing_twist(&self.rotation, &axis);
+        let twist_angle = crate::convert::twist_angle(&twist, &axis);
+        ui.label(format!(
+            "Swing: {}  angle {:.4} rad ({:.2}°)",
+            format_quat(&swing),
+            swing.angle(),
+            swing.angle().to_degrees()
+        ));
+        ui.label(format!(
+            "Twist: {}  angle {twist_angle:.4} rad ({:.2}°)",
+            format_quat(&twist),
+            twist_angle.to_degrees()
+        ));
+        let recomposed = swing * twist;
+        ui.label(format!(
+            "Swing · twist: {}  differs from the current rotation by {:.2e} rad",
+            format_quat(&recomposed),
+            recomposed.angle_to(&self.rotation)
+        ));
+    }
 }
 
 impl eframe::App for TemplateApp {
@@ -347,25 +1010,11 @@ impl eframe::App for TemplateApp {
                     if self.edited { "(Unsync)" } else { "(Sync)" }
                 ));
                 ui.separator();
-                ui.label(egui::RichText::new("Quaternion:").heading());
-                ui.separator();
-                ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                    self.quaternion_view(egui_extras::StripBuilder::new(ui), &mut rotation_repr);
-                });
-                ui.separator();
-                ui.label(egui::RichText::new("Angle-axis:").heading());
-                ui.separator();
-                ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                    self.angleaxis_view(egui_extras::StripBuilder::new(ui), &mut rotation_repr);
-                });
-                ui.separator();
-                ui.label(egui::RichText::new("Rotation matrix:").heading());
-                ui.separator();
-                ui.allocate_ui_with_layout([ui.available_size_before_wrap().x, 0.0].into(), egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                    self.rotation_matrix_view(egui_extras::StripBuilder::new(ui), &mut rotation_repr);
-                });
+                self.representation_views(ui, &mut rotation_repr);
                 ui.separator();
                 self.raw_string_access(ui, &mut rotation_repr);
+                ui.separator();
+                self.tools_ui(ui, &mut rotation_repr);
                 if ui.available_height() > self.footer_height {
                     self.footer_height = ui
                         .with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -388,12 +1037,47 @@ impl eframe::App for TemplateApp {
             });
         });
 
+        #[cfg(target_arch = "wasm32")]
+        if let Some((quat, translation)) = crate::web::take_pending(ui.ctx()) {
+            rotation_repr = Some(match translation {
+                Some(translation) => {
+                    RotationRepr::Transform(na::Isometry3::from_parts(translation.into(), quat))
+                }
+                None => RotationRepr::Computed(quat),
+            });
+        }
+
         if let Some(rotation_repr) = rotation_repr
             && self.update_input(&rotation_repr).is_ok()
         {
             self.edited = false;
         }
+
+        #[cfg(target_arch = "wasm32")]
+        crate::web::publish(self.current_transform());
+    }
+}
+
+fn format_quat(quat: &na::UnitQuaternion<f64>) -> String {
+    format!(
+        "[{:.4}, {:.4}, {:.4}, {:.4}]",
+        quat.w, quat.i, quat.j, quat.k
+    )
+}
+
+/// Euler view fields labelled with their axes, `angles` in radians.
+fn euler_fields(sequence: EulerSequence, degrees: bool, angles: [f64; 3]) -> [(String, String); 3] {
+    let unit = if degrees { "deg" } else { "rad" };
+    let mut fields = sequence.axes().map(|axis| {
+        (
+            format!("{} ({unit})", crate::euler::axis_name(axis)),
+            String::new(),
+        )
+    });
+    for (field, angle) in fields.iter_mut().zip(angles) {
+        field.1 = format!("{:.4}", if degrees { angle.to_degrees() } else { angle });
     }
+    fields
 }
 
 fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
@@ -0,0 +1,217 @@
+//! Mean of a set of rotations, for repeated measurements of the same attitude.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+use crate::raw::RawStringType;
+
+/// Iteration cap of [`karcher_mean`]; it converges in a handful of steps for clustered inputs.
+const KARCHER_MAX_ITERATIONS: usize = 100;
+
+/// Splits `text` into consecutive rotations of `layout`.
+///
+/// # Errors
+/// When the number count is not a multiple of the layout size, or a rotation fails to parse.
+pub fn parse_rotation_list(
+    text: &str,
+    layout: RawStringType,
+) -> anyhow::Result<Vec<na::UnitQuaternion<f64>>> {
+    if matches!(layout, RawStringType::Tf2Echo | RawStringType::RosYaml) {
+        return crate::ros::split_messages(text)
+            .iter()
+            .map(|message| layout.parse(&layout.numbers(message)?))
+            .collect();
+    }
+    let nums = crate::split_numbers(text);
+    let size = layout.number_count();
+    if nums.is_empty() || !nums.len().is_multiple_of(size) {
+        anyhow::bail!(
+            "{} numbers is not a multiple of {size} ({layout:?})",
+            nums.len()
+        );
+    }
+    nums.chunks(size).map(|chunk| layout.parse(chunk)).collect()
+}
+
+/// Chordal L2 mean: the dominant eigenvector of `Σ qqᵀ` (Markley et al. 2007).
+///
+/// Insensitive to the sign of each quaternion. Returns `None` for an empty set.
+pub fn chordal_mean(quats: &[na::UnitQuaternion<f64>]) -> Option<na::UnitQuaternion<f64>> {
+    if quats.is_empty() {
+        return None;
+    }
+    let accumulator = quats.iter().fold(na::Matrix4::zeros(), |acc, quat| {
+        acc + quat.coords * quat.coords.transpose()
+    });
+    let eigen = accumulator.symmetric_eigen();
+    let dominant = eigen.eigenvalues.imax();
+    Some(na::UnitQuaternion::from_quaternion(
+        na::Quaternion::from_vector(eigen.eigenvectors.column(dominant).into_owned()),
+    ))
+}
+
+/// Geodesic L2 (Karcher) mean, found by gradient descent on the rotation manifold from `initial`.
+pub fn karcher_mean(
+    quats: &[na::UnitQuaternion<f64>],
+    initial: na::UnitQuaternion<f64>,
+) -> na::UnitQuaternion<f64> {
+    let mut mean = initial;
+    for _ in 0..KARCHER_MAX_ITERATIONS {
+        let step = quats
+            .iter()
+            .map(|quat| (mean.inverse() * quat).scaled_axis())
+            .sum::<na::Vector3<f64>>()
+            / quats.len().max(1) as f64;
+        mean *= na::UnitQuaternion::from_scaled_axis(step);
+        if step.norm() < 1e-12 {
+            break;
+        }
+    }
+    mean
+}
+
+/// Maximum and RMS angle, in radians, between `mean` and each rotation.
+pub fn spread(mean: &na::UnitQuaternion<f64>, quats: &[na::UnitQuaternion<f64>]) -> (f64, f64) {
+    let angles = quats.iter().map(|quat| mean.angle_to(quat));
+    let max = angles.clone().fold(0.0, f64::max);
+    let rms = (angles.map(|angle| angle * angle).sum::<f64>() / quats.len().max(1) as f64).sqrt();
+    (max, rms)
+}
+
+struct Means {
+    quats: Vec<na::UnitQuaternion<f64>>,
+    chordal: na::UnitQuaternion<f64>,
+    karcher: na::UnitQuaternion<f64>,
+}
+
+/// State of the rotation averaging panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AverageTool {
+    input: String,
+    layout: RawStringType,
+    #[serde(skip)]
+    result: Option<anyhow::Result<Means>>,
+}
+
+impl Default for AverageTool {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            layout: RawStringType::QuaternionWXYZ,
+            result: None,
+        }
+    }
+}
+
+impl AverageTool {
+    /// Returns the mean the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::UnitQuaternion<f64>> {
+        ui.horizontal(|ui| {
+            if ui.button("compute").clicked() {
+                self.result = Some(self.compute());
+            }
+            egui::ComboBox::from_label("rotation layout")
+                .selected_text(format!("{:?}", self.layout))
+                .show_ui(ui, |ui| {
+                    for string_type in RawStringType::iter() {
+                        ui.selectable_value(
+                            &mut self.layout,
+                            string_type,
+                            format!("{string_type:?}"),
+                        );
+                    }
+                });
+        });
+        ui.add(
+            egui::TextEdit::multiline(&mut self.input)
+                .desired_width(f32::INFINITY)
+                .desired_rows(6)
+                .hint_text("one rotation after another, in the chosen layout"),
+        );
+        let mut loaded = None;
+        match &self.result {
+            Some(Ok(means)) => {
+                ui.label(format!("{} rotations", means.quats.len()));
+                for (name, mean) in [("Chordal", means.chordal), ("Karcher", means.karcher)] {
+                    let (max, rms) = spread(&mean, &means.quats);
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{name} mean: [{:.4}, {:.4}, {:.4}, {:.4}]  max {:.4}°, RMS {:.4}°",
+                            mean.w,
+                            mean.i,
+                            mean.j,
+                            mean.k,
+                            max.to_degrees(),
+                            rms.to_degrees()
+                        ));
+                        if ui.small_button("load").clicked() {
+                            loaded = Some(mean);
+                        }
+                    });
+                }
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+            None => {}
+        }
+        loaded
+    }
+
+    fn compute(&self) -> anyhow::Result<Means> {
+        let quats = parse_rotation_list(&self.input, self.layout)?;
+        let chordal = chordal_mean(&quats).ok_or_else(|| anyhow::anyhow!("no rotations"))?;
+        let karcher = karcher_mean(&quats, chordal);
+        Ok(Means {
+            quats,
+            chordal,
+            karcher,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_means_of_symmetric_spread() {
+        let center = na::UnitQuaternion::from_euler_angles(0.4, -0.2, 1.0);
+        let quats = [
+            na::Vector3::x(),
+            -na::Vector3::x(),
+            na::Vector3::y(),
+            -na::Vector3::y(),
+        ]
+        .iter()
+        .map(|axis| center * na::UnitQuaternion::from_scaled_axis(axis * 0.1))
+        // A flipped sign must not change the mean.
+        .map(|quat| na::UnitQuaternion::new_unchecked(-quat.into_inner()))
+        .collect::<Vec<_>>();
+        let chordal = chordal_mean(&quats).expect("non-empty");
+        assert!(
+            chordal.angle_to(&center) < 1e-9,
+            "chordal mean is the center"
+        );
+        let karcher = karcher_mean(&quats, chordal);
+        assert!(
+            karcher.angle_to(&center) < 1e-9,
+            "Karcher mean is the center"
+        );
+        let (max, rms) = spread(&karcher, &quats);
+        assert!((max - 0.1).abs() < 1e-9, "max deviation");
+        assert!((rms - 0.1).abs() < 1e-9, "RMS deviation");
+    }
+
+    #[test]
+    fn test_parse_rotation_list() {
+        let quats = parse_rotation_list("1 0 0 0\n0 1 0 0", RawStringType::QuaternionWXYZ)
+            .expect("two quaternions");
+        assert_eq!(quats.len(), 2, "two rotations");
+        assert!(
+            parse_rotation_list("1 0 0 0 1", RawStringType::QuaternionWXYZ).is_err(),
+            "partial rotation is rejected"
+        );
+    }
+}
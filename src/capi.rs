@@ -0,0 +1,472 @@
+//! A C API over the conversion core, built as a shared library with
+//! `cargo rustc --release --lib --features capi --crate-type cdylib`.
+//!
+//! The header `include/rottool.h` is checked in; after changing this file, regenerate it with
+//! `cbindgen --config cbindgen.toml --output include/rottool.h`. Every function returns a
+//! [`Status`]; on failure [`rottool_last_error`] describes what went wrong. The enums have fixed
+//! values, so new variants are only ever appended.
+//!
+//! ```c
+//! double rotvec[3] = {0.1, 0.2, 0.3};
+//! double matrix[9];
+//! if (rottool_convert(ROTTOOL_REPRESENTATION_ROTATION_VECTOR, rotvec, 3,
+//!                     ROTTOOL_REPRESENTATION_ROTATION_MATRIX_COLUMN_MAJOR, matrix, 9,
+//!                     ROTTOOL_EULER_SEQUENCE_ZYX, ROTTOOL_EULER_FRAME_INTRINSIC) != ROTTOOL_STATUS_OK) {
+//!     fprintf(stderr, "%s\n", rottool_last_error());
+//! }
+//! ```
+
+#![expect(
+    unsafe_code,
+    reason = "a C API takes raw pointers and exports unmangled symbols"
+)]
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+
+use crate::euler;
+use crate::raw::RawStringType;
+use crate::repr;
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    NullPointer = 1,
+    /// Wrong number of values, a zero quaternion or axis, or text that does not parse.
+    InvalidArgument = 2,
+    /// The output buffer is too short; the needed length was still reported where possible.
+    BufferTooSmall = 3,
+}
+
+/// Flat number layouts for [`rottool_convert`]. The functions take these as `uint32_t`, so a
+/// value from C is checked rather than trusted to be a variant.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum Representation {
+    QuaternionWxyz = 0,
+    QuaternionXyzw = 1,
+    /// Angle in radians, then the axis.
+    AngleAxis = 2,
+    /// Axis times angle in radians.
+    RotationVector = 3,
+    Mrp = 4,
+    Gibbs = 5,
+    /// Three angles in radians, in the given sequence and frame.
+    Euler = 6,
+    RotationMatrixRowMajor = 7,
+    RotationMatrixColumnMajor = 8,
+}
+
+/// Text layouts for [`rottool_parse`] and [`rottool_format`], as in the raw string box.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum Layout {
+    ColumnMajor4x4 = 0,
+    RowMajor4x4 = 1,
+    ColumnMajor3x3 = 2,
+    RowMajor3x3 = 3,
+    QuaternionWxyz = 4,
+    QuaternionXyzw = 5,
+    Continuous6d = 6,
+    Svd9d = 7,
+    DualQuaternionWxyz = 8,
+    DualQuaternionXyzw = 9,
+    Tf2Echo = 10,
+    RosYaml = 11,
+    RotationVector = 12,
+    RollPitchYaw = 13,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum EulerSequence {
+    Xyz = 0,
+    Xzy = 1,
+    Yxz = 2,
+    Yzx = 3,
+    Zxy = 4,
+    Zyx = 5,
+    Xyx = 6,
+    Xzx = 7,
+    Yxy = 8,
+    Yzy = 9,
+    Zxz = 10,
+    Zyz = 11,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum EulerFrame {
+    Intrinsic = 0,
+    Extrinsic = 1,
+}
+
+/// The variant of `E` with discriminant `value`, or [`Status::InvalidArgument`].
+fn variant<E: strum::IntoEnumIterator + Copy>(
+    value: u32,
+    discriminant: fn(E) -> u32,
+    name: &str,
+) -> Result<E, Status> {
+    E::iter()
+        .find(|&variant| discriminant(variant) == value)
+        .ok_or_else(|| fail(Status::InvalidArgument, format!("{value} is not a {name}")))
+}
+
+impl TryFrom<u32> for Representation {
+    type Error = Status;
+
+    fn try_from(value: u32) -> Result<Self, Status> {
+        variant(value, |variant| variant as u32, "representation")
+    }
+}
+
+impl TryFrom<u32> for Layout {
+    type Error = Status;
+
+    fn try_from(value: u32) -> Result<Self, Status> {
+        variant(value, |variant| variant as u32, "layout")
+    }
+}
+
+impl TryFrom<u32> for EulerSequence {
+    type Error = Status;
+
+    fn try_from(value: u32) -> Result<Self, Status> {
+        variant(value, |variant| variant as u32, "Euler sequence")
+    }
+}
+
+impl TryFrom<u32> for EulerFrame {
+    type Error = Status;
+
+    fn try_from(value: u32) -> Result<Self, Status> {
+        variant(value, |variant| variant as u32, "Euler frame")
+    }
+}
+
+impl From<Representation> for repr::Representation {
+    fn from(representation: Representation) -> Self {
+        match representation {
+            Representation::QuaternionWxyz => Self::QuaternionWXYZ,
+            Representation::QuaternionXyzw => Self::QuaternionXYZW,
+            Representation::AngleAxis => Self::AngleAxis,
+            Representation::RotationVector => Self::RotationVector,
+            Representation::Mrp => Self::Mrp,
+            Representation::Gibbs => Self::Gibbs,
+            Representation::Euler => Self::Euler,
+            Representation::RotationMatrixRowMajor => Self::RotationMatrix,
+            Representation::RotationMatrixColumnMajor => Self::RotationMatrixColumnMajor,
+        }
+    }
+}
+
+impl From<Layout> for RawStringType {
+    fn from(layout: Layout) -> Self {
+        match layout {
+            Layout::ColumnMajor4x4 => Self::ColumnMajor4x4,
+            Layout::RowMajor4x4 => Self::RowMajor4x4,
+            Layout::ColumnMajor3x3 => Self::ColumnMajor3x3,
+            Layout::RowMajor3x3 => Self::RowMajor3x3,
+            Layout::QuaternionWxyz => Self::QuaternionWXYZ,
+            Layout::QuaternionXyzw => Self::QuaternionXYZW,
+            Layout::Continuous6d => Self::Continuous6D,
+            Layout::Svd9d => Self::Svd9D,
+            Layout::DualQuaternionWxyz => Self::DualQuaternionWXYZ,
+            Layout::DualQuaternionXyzw => Self::DualQuaternionXYZW,
+            Layout::Tf2Echo => Self::Tf2Echo,
+            Layout::RosYaml => Self::RosYaml,
+            Layout::RotationVector => Self::RotationVector,
+            Layout::RollPitchYaw => Self::RollPitchYaw,
+        }
+    }
+}
+
+impl From<EulerSequence> for euler::EulerSequence {
+    fn from(sequence: EulerSequence) -> Self {
+        match sequence {
+            EulerSequence::Xyz => Self::XYZ,
+            EulerSequence::Xzy => Self::XZY,
+            EulerSequence::Yxz => Self::YXZ,
+            EulerSequence::Yzx => Self::YZX,
+            EulerSequence::Zxy => Self::ZXY,
+            EulerSequence::Zyx => Self::ZYX,
+            EulerSequence::Xyx => Self::XYX,
+            EulerSequence::Xzx => Self::XZX,
+            EulerSequence::Yxy => Self::YXY,
+            EulerSequence::Yzy => Self::YZY,
+            EulerSequence::Zxz => Self::ZXZ,
+            EulerSequence::Zyz => Self::ZYZ,
+        }
+    }
+}
+
+impl From<EulerFrame> for euler::EulerFrame {
+    fn from(frame: EulerFrame) -> Self {
+        match frame {
+            EulerFrame::Intrinsic => Self::Intrinsic,
+            EulerFrame::Extrinsic => Self::Extrinsic,
+        }
+    }
+}
+
+/// Records `message` for [`rottool_last_error`] and returns `status`.
+fn fail(status: Status, message: impl std::fmt::Display) -> Status {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with_borrow_mut(|last| *last = message);
+    status
+}
+
+fn invalid(error: &anyhow::Error) -> Status {
+    fail(Status::InvalidArgument, format!("{error:#}"))
+}
+
+fn status(result: Result<(), Status>) -> Status {
+    result.err().unwrap_or(Status::Ok)
+}
+
+/// # Safety
+/// `ptr` must be null or point to `len` readable values that outlive the returned slice.
+unsafe fn input<'a>(ptr: *const f64, len: usize, name: &str) -> Result<&'a [f64], Status> {
+    if ptr.is_null() {
+        return Err(fail(Status::NullPointer, format!("{name} is null")));
+    }
+    // SAFETY: non-null, and the caller vouches for `len` values.
+    Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+/// # Safety
+/// `ptr` must be null or point to `len` writable values that outlive the returned slice.
+unsafe fn output<'a>(ptr: *mut f64, len: usize, name: &str) -> Result<&'a mut [f64], Status> {
+    if ptr.is_null() {
+        return Err(fail(Status::NullPointer, format!("{name} is null")));
+    }
+    // SAFETY: non-null, and the caller vouches for `len` values.
+    Ok(unsafe { std::slice::from_raw_parts_mut(ptr, len) })
+}
+
+/// How many numbers one rotation takes in `representation`, a [`Representation`], or 0 when it
+/// is not one.
+#[unsafe(no_mangle)]
+pub extern "C" fn rottool_number_count(representation: u32) -> usize {
+    Representation::try_from(representation).map_or(0, |representation| {
+        repr::Representation::from(representation).number_count()
+    })
+}
+
+/// Converts `values_len` numbers in `from` into `to`, both a [`Representation`], writing at most
+/// `out_len` numbers to `out`. Euler angles are read and written in `sequence`, an
+/// [`EulerSequence`], and `frame`, an [`EulerFrame`].
+///
+/// # Safety
+/// `values` must point to `values_len` doubles and `out` to `out_len` doubles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rottool_convert(
+    from: u32,
+    values: *const f64,
+    values_len: usize,
+    to: u32,
+    out: *mut f64,
+    out_len: usize,
+    sequence: u32,
+    frame: u32,
+) -> Status {
+    status((|| {
+        let (from, to) = (
+            repr::Representation::from(Representation::try_from(from)?),
+            repr::Representation::from(Representation::try_from(to)?),
+        );
+        let sequence = euler::EulerSequence::from(EulerSequence::try_from(sequence)?);
+        let frame = euler::EulerFrame::from(EulerFrame::try_from(frame)?);
+        // SAFETY: forwarded from the caller.
+        let values = unsafe { input(values, values_len, "values") }?;
+        // SAFETY: forwarded from the caller.
+        let out = unsafe { output(out, out_len, "out") }?;
+        let quat = from
+            .quat(values, sequence, frame)
+            .map_err(|e| invalid(&e))?;
+        let converted = to.values(&quat, sequence, frame);
+        let Some(out) = out.get_mut(..converted.len()) else {
+            return Err(fail(
+                Status::BufferTooSmall,
+                format!(
+                    "{to:?} takes {} numbers, out has {out_len}",
+                    converted.len()
+                ),
+            ));
+        };
+        out.copy_from_slice(&converted);
+        Ok(())
+    })())
+}
+
+/// Reads one rotation from the NUL-terminated `text` in `layout`, a [`Layout`], into `quat_wxyz`
+/// (4 doubles). `translation` (3 doubles) and `has_translation` may be null; the translation is
+/// zero for layouts without one.
+///
+/// # Safety
+/// `text` must be NUL-terminated, and the other pointers null or valid for their lengths.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rottool_parse(
+    text: *const c_char,
+    layout: u32,
+    quat_wxyz: *mut f64,
+    translation: *mut f64,
+    has_translation: *mut bool,
+) -> Status {
+    status((|| {
+        let layout = RawStringType::from(Layout::try_from(layout)?);
+        if text.is_null() {
+            return Err(fail(Status::NullPointer, "text is null"));
+        }
+        // SAFETY: non-null, and the caller vouches for the terminator.
+        let text = unsafe { CStr::from_ptr(text) }.to_string_lossy();
+        // SAFETY: forwarded from the caller.
+        let quat_out = unsafe { output(quat_wxyz, 4, "quat_wxyz") }?;
+        let (quat, parsed_translation) = repr::parse_raw(&text, layout).map_err(|e| invalid(&e))?;
+        quat_out.copy_from_slice(&[quat.w, quat.i, quat.j, quat.k]);
+        if !translation.is_null() {
+            // SAFETY: non-null, so valid for 3 values per the contract.
+            let translation = unsafe { output(translation, 3, "translation") }?;
+            translation.copy_from_slice(parsed_translation.unwrap_or_default().as_slice());
+        }
+        if !has_translation.is_null() {
+            // SAFETY: non-null, so writable per the contract.
+            unsafe { has_translation.write(parsed_translation.is_some()) };
+        }
+        Ok(())
+    })())
+}
+
+/// Formats `quat_wxyz` (4 doubles) and `translation` (3 doubles, or null for zero) as text in
+/// `layout`, a [`Layout`]. Writes the text and a NUL to `buffer` when it holds `capacity` bytes,
+/// and its length without the NUL to `length` when that is not null. Call with a null buffer
+/// and zero capacity to ask for the length.
+///
+/// # Safety
+/// `buffer` must be null or valid for `capacity` bytes, and the other pointers null or valid
+/// for their lengths.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rottool_format(
+    layout: u32,
+    quat_wxyz: *const f64,
+    translation: *const f64,
+    buffer: *mut c_char,
+    capacity: usize,
+    length: *mut usize,
+) -> Status {
+    status((|| {
+        let layout = RawStringType::from(Layout::try_from(layout)?);
+        // SAFETY: forwarded from the caller.
+        let quat = unsafe { input(quat_wxyz, 4, "quat_wxyz") }?;
+        let quat = repr::Representation::QuaternionWXYZ
+            .quat(
+                quat,
+                euler::EulerSequence::ZYX,
+                euler::EulerFrame::Intrinsic,
+            )
+            .map_err(|e| invalid(&e))?;
+        let translation = if translation.is_null() {
+            nalgebra::Vector3::zeros()
+        } else {
+            // SAFETY: non-null, so valid for 3 values per the contract.
+            nalgebra::Vector3::from_column_slice(unsafe { input(translation, 3, "translation") }?)
+        };
+        let text = layout.format_transform(&quat, &translation);
+        if !length.is_null() {
+            // SAFETY: non-null, so writable per the contract.
+            unsafe { length.write(text.len()) };
+        }
+        if buffer.is_null() || capacity <= text.len() {
+            return Err(fail(
+                Status::BufferTooSmall,
+                format!(
+                    "the text takes {} bytes, buffer has {capacity}",
+                    text.len() + 1
+                ),
+            ));
+        }
+        // SAFETY: `buffer` holds `capacity` bytes, more than the text.
+        unsafe {
+            buffer.copy_from_nonoverlapping(text.as_ptr().cast(), text.len());
+            buffer.add(text.len()).write(0);
+        }
+        Ok(())
+    })())
+}
+
+/// The message of the last failure on this thread, valid until the next failure on it.
+#[unsafe(no_mangle)]
+pub extern "C" fn rottool_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|last| last.as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_and_format() {
+        let rotvec = [0.0, 0.0, std::f64::consts::FRAC_PI_2];
+        let mut matrix = [0.0; 9];
+        // SAFETY: both arrays have the lengths passed.
+        let converted = unsafe {
+            rottool_convert(
+                Representation::RotationVector as u32,
+                rotvec.as_ptr(),
+                3,
+                Representation::RotationMatrixColumnMajor as u32,
+                matrix.as_mut_ptr(),
+                9,
+                EulerSequence::Zyx as u32,
+                EulerFrame::Intrinsic as u32,
+            )
+        };
+        assert_eq!(converted, Status::Ok, "valid conversion");
+        assert_eq!(rottool_number_count(99), 0, "unknown representation");
+        // The first column is the rotated x axis.
+        let expected = [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        for (value, expected) in matrix.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12, "{matrix:?}");
+        }
+
+        let quat = [1.0, 0.0, 0.0, 0.0];
+        let mut length = 0;
+        // SAFETY: a null buffer with zero capacity only asks for the length.
+        let probed = unsafe {
+            rottool_format(
+                Layout::QuaternionWxyz as u32,
+                quat.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                0,
+                &raw mut length,
+            )
+        };
+        assert_eq!(probed, Status::BufferTooSmall, "length query");
+        let mut buffer = vec![0; length + 1];
+        // SAFETY: the buffer holds `length + 1` bytes.
+        let formatted = unsafe {
+            rottool_format(
+                Layout::QuaternionWxyz as u32,
+                quat.as_ptr(),
+                std::ptr::null(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(formatted, Status::Ok, "large enough buffer");
+        // SAFETY: `rottool_format` terminated the text.
+        let text = unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_string_lossy();
+        assert_eq!(
+            text,
+            RawStringType::QuaternionWXYZ.format(&nalgebra::UnitQuaternion::identity()),
+            "same text as the GUI"
+        );
+    }
+}
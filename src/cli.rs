@@ -0,0 +1,91 @@
+//! Command-line operations on trajectory files, run instead of the GUI when arguments are given.
+
+use std::io::{Read as _, Write as _};
+
+use strum::IntoEnumIterator as _;
+
+use crate::trajectory::TrajectoryFormat;
+
+const USAGE: &str = "usage: rottool continuity <tum|kitti|euroc|colmap> [INPUT [OUTPUT]]
+
+continuity  flip q to -q wherever a sample leaves the previous sample's hemisphere,
+            and write the corrected trajectory in the same format.
+            INPUT and OUTPUT default to stdin and stdout; the flip count goes to stderr.";
+
+fn parse_format(name: &str) -> anyhow::Result<TrajectoryFormat> {
+    TrajectoryFormat::iter()
+        .find(|format| format!("{format:?}").eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow::anyhow!("unknown trajectory format {name:?}\n\n{USAGE}"))
+}
+
+/// Runs the command in `args`, without the program name.
+///
+/// # Errors
+/// On unknown commands or formats, unreadable input, unwritable output, or malformed input.
+pub fn run_cli(args: &[String]) -> anyhow::Result<()> {
+    let [command, format, paths @ ..] = args else {
+        anyhow::bail!("{USAGE}");
+    };
+    if command != "continuity" || paths.len() > 2 {
+        anyhow::bail!("{USAGE}");
+    }
+    let format = parse_format(format)?;
+    let text = if let Some(path) = paths.first() {
+        std::fs::read_to_string(path)?
+    } else {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    };
+    let (corrected, flipped) = crate::trajectory::make_text_continuous(format, &text)?;
+    match paths.get(1) {
+        Some(path) => std::fs::write(path, corrected)?,
+        None => write!(std::io::stdout(), "{corrected}")?,
+    }
+    writeln!(std::io::stderr(), "flipped {flipped} samples")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continuity() {
+        let text = "0 0 0 0 0 0 0 1\n1 0 0 0 0 0 -0.1 -0.995\n2 0 0 0 0 0 -0.2 -0.98\n3 0 0 0 0 0 0.3 0.954\n";
+        let (corrected, flipped) = crate::trajectory::make_text_continuous(
+            parse_format("TUM").expect("known format"),
+            text,
+        )
+        .expect("valid");
+        assert_eq!(flipped, 2, "the two negative-w samples flip");
+        let poses = crate::trajectory::parse(TrajectoryFormat::Tum, &corrected).expect("valid");
+        assert!(
+            poses.iter().all(|stamped| stamped.pose.rotation.w > 0.0),
+            "every sample in the first sample's hemisphere"
+        );
+    }
+
+    #[test]
+    fn test_continuity_keeps_other_fields() {
+        let text = "# IMAGE_ID, QW, QX, QY, QZ, TX, TY, TZ, CAMERA_ID, NAME\n\
+            # POINTS2D[] as (X, Y, POINT3D_ID)\n\
+            17 1 0 0 0 1.5 -2 3 4 a.png\n\
+            10.5 20.25 -1 3.0 4.0 42\n\
+            5 -0.995 0 +0.1 0 0.25 0.5 0.75 2 b.png\n\
+            \n\
+            23 0.98 0 -0.2 0 1 2 3 4 c d.png\n\
+            1 2 3\n";
+        let (corrected, flipped) = crate::trajectory::make_text_continuous(
+            parse_format("colmap").expect("known format"),
+            text,
+        )
+        .expect("valid");
+        assert_eq!(flipped, 1, "the negative-w sample flips");
+        assert_eq!(
+            corrected,
+            text.replace("5 -0.995 0 +0.1 0 ", "5 0.995 -0 -0.1 -0 "),
+            "only the flipped sample's quaternion changes"
+        );
+    }
+}
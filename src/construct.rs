@@ -0,0 +1,240 @@
+//! Rotations constructed from vectors: shortest arc and look-at.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+/// `|a · b|` above which two unit vectors are treated as parallel or antiparallel.
+const PARALLEL_THRESHOLD: f64 = 1.0 - 1e-9;
+
+/// Axis conventions of the rotated (camera or body) frame for [`look_at`].
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum CameraConvention {
+    /// Looks along -Z with +Y up and +X right (OpenGL, glTF).
+    OpenGl,
+    /// Looks along +Z with +Y down and +X right (`OpenCV`, COLMAP).
+    OpenCv,
+    /// Looks along +X with +Z up and +Y left (ROS REP-103 body frame).
+    Ros,
+}
+
+/// A unit vector perpendicular to `v`, built from the world axis least aligned with it.
+fn any_perpendicular(v: &na::UnitVector3<f64>) -> na::UnitVector3<f64> {
+    let least_aligned = if v.x.abs() <= v.y.abs() && v.x.abs() <= v.z.abs() {
+        na::Vector3::x()
+    } else if v.y.abs() <= v.z.abs() {
+        na::Vector3::y()
+    } else {
+        na::Vector3::z()
+    };
+    na::UnitVector3::new_normalize(v.cross(&least_aligned))
+}
+
+/// Shortest-arc rotation taking the direction of `from` onto the direction of `to`.
+///
+/// Antiparallel inputs have no unique shortest arc; they get a half turn about
+/// [`any_perpendicular`], which is also returned so the choice can be shown.
+///
+/// # Errors
+/// When either vector is zero.
+pub fn shortest_arc(
+    from: &na::Vector3<f64>,
+    to: &na::Vector3<f64>,
+) -> anyhow::Result<(na::UnitQuaternion<f64>, Option<na::UnitVector3<f64>>)> {
+    let (Some(from), Some(to)) = (
+        na::UnitVector3::try_new(*from, f64::EPSILON),
+        na::UnitVector3::try_new(*to, f64::EPSILON),
+    ) else {
+        anyhow::bail!("vectors must be nonzero");
+    };
+    if from.dot(&to) < -PARALLEL_THRESHOLD {
+        let axis = any_perpendicular(&from);
+        return Ok((
+            na::UnitQuaternion::from_axis_angle(&axis, std::f64::consts::PI),
+            Some(axis),
+        ));
+    }
+    // q = (1 + a·b, a×b) normalized is the half-angle rotation from a to b.
+    let quat = na::Quaternion::from_parts(1.0 + from.dot(&to), from.cross(&to));
+    Ok((na::UnitQuaternion::from_quaternion(quat), None))
+}
+
+/// Rotation of a camera (or body) frame looking along `forward`, with its up axis as close to
+/// `up` as possible. The columns of the rotation matrix are the frame axes in world coordinates.
+///
+/// When `up` is parallel or antiparallel to `forward`, [`any_perpendicular`] replaces it and
+/// is returned.
+///
+/// # Errors
+/// When either vector is zero.
+pub fn look_at(
+    forward: &na::Vector3<f64>,
+    up: &na::Vector3<f64>,
+    convention: CameraConvention,
+) -> anyhow::Result<(na::UnitQuaternion<f64>, Option<na::UnitVector3<f64>>)> {
+    let (Some(forward), Some(up)) = (
+        na::UnitVector3::try_new(*forward, f64::EPSILON),
+        na::UnitVector3::try_new(*up, f64::EPSILON),
+    ) else {
+        anyhow::bail!("vectors must be nonzero");
+    };
+    let substituted_up =
+        (forward.dot(&up).abs() > PARALLEL_THRESHOLD).then(|| any_perpendicular(&forward));
+    let right = forward.cross(&substituted_up.unwrap_or(up)).normalize();
+    let up = right.cross(&forward);
+    let forward = forward.into_inner();
+    let columns = match convention {
+        CameraConvention::OpenGl => [right, up, -forward],
+        CameraConvention::OpenCv => [right, -up, forward],
+        CameraConvention::Ros => [forward, -right, up],
+    };
+    let rotation = na::Rotation3::from_matrix_unchecked(na::Matrix3::from_columns(&columns));
+    Ok((
+        na::UnitQuaternion::from_rotation_matrix(&rotation),
+        substituted_up,
+    ))
+}
+
+/// State of the constructor panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ConstructTool {
+    from: [(String, String); 3],
+    to: [(String, String); 3],
+    forward: [(String, String); 3],
+    up: [(String, String); 3],
+    convention: CameraConvention,
+}
+
+impl Default for ConstructTool {
+    fn default() -> Self {
+        Self {
+            from: crate::vector3_fields([1.0, 0.0, 0.0]),
+            to: crate::vector3_fields([0.0, 1.0, 0.0]),
+            forward: crate::vector3_fields([1.0, 0.0, 0.0]),
+            up: crate::vector3_fields([0.0, 0.0, 1.0]),
+            convention: CameraConvention::OpenGl,
+        }
+    }
+}
+
+impl ConstructTool {
+    /// Returns the constructed rotation the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::UnitQuaternion<f64>> {
+        let mut loaded = None;
+
+        ui.label(egui::RichText::new("Shortest arc from a to b").strong());
+        ui.label("a:");
+        crate::vector3_input(ui, &mut self.from);
+        ui.label("b:");
+        crate::vector3_input(ui, &mut self.to);
+        let arc = crate::parse_vector3(&self.from)
+            .and_then(|from| shortest_arc(&from, &crate::parse_vector3(&self.to)?));
+        result_ui(ui, arc, "a and b are antiparallel", &mut loaded);
+
+        ui.separator();
+        ui.label(egui::RichText::new("Look-at").strong());
+        ui.label("forward:");
+        crate::vector3_input(ui, &mut self.forward);
+        ui.label("up:");
+        crate::vector3_input(ui, &mut self.up);
+        egui::ComboBox::from_label("camera convention")
+            .selected_text(format!("{:?}", self.convention))
+            .show_ui(ui, |ui| {
+                for convention in CameraConvention::iter() {
+                    ui.selectable_value(
+                        &mut self.convention,
+                        convention,
+                        format!("{convention:?}"),
+                    );
+                }
+            });
+        let look = crate::parse_vector3(&self.forward).and_then(|forward| {
+            look_at(&forward, &crate::parse_vector3(&self.up)?, self.convention)
+        });
+        result_ui(ui, look, "up is parallel to forward", &mut loaded);
+
+        loaded
+    }
+}
+
+fn result_ui(
+    ui: &mut egui::Ui,
+    result: anyhow::Result<(na::UnitQuaternion<f64>, Option<na::UnitVector3<f64>>)>,
+    degenerate: &str,
+    loaded: &mut Option<na::UnitQuaternion<f64>>,
+) {
+    match result {
+        Ok((quat, substitute)) => {
+            if let Some(axis) = substitute {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{degenerate}: using [{:.4}, {:.4}, {:.4}] instead",
+                        axis.x, axis.y, axis.z
+                    ),
+                );
+            }
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "[{:.4}, {:.4}, {:.4}, {:.4}]",
+                    quat.w, quat.i, quat.j, quat.k
+                ));
+                if ui.small_button("load").clicked() {
+                    *loaded = Some(quat);
+                }
+            });
+        }
+        Err(e) => {
+            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_arc() {
+        let from = na::Vector3::new(1.0, 2.0, -0.5);
+        for to in [na::Vector3::new(-0.3, 0.2, 4.0), -from * 2.0] {
+            let (quat, _) = shortest_arc(&from, &to).expect("nonzero vectors");
+            assert!(
+                (quat * from).normalize().dot(&to.normalize()) > 1.0 - 1e-12,
+                "maps a onto b"
+            );
+        }
+        assert!(
+            shortest_arc(&from, &-from).expect("nonzero").1.is_some(),
+            "antiparallel inputs are reported"
+        );
+    }
+
+    #[test]
+    fn test_look_at() {
+        let forward = na::Vector3::new(1.0, 1.0, 0.0);
+        let up = na::Vector3::z();
+        let (quat, substitute) =
+            look_at(&forward, &up, CameraConvention::OpenGl).expect("nonzero vectors");
+        assert!(substitute.is_none(), "up is usable");
+        assert!(
+            (quat * -na::Vector3::z() - forward.normalize()).norm() < 1e-12,
+            "OpenGL camera looks along -Z"
+        );
+        assert!((quat * na::Vector3::y() - up).norm() < 1e-12, "+Y is up");
+        let (quat, _) = look_at(&forward, &up, CameraConvention::OpenCv).expect("nonzero");
+        assert!(
+            (quat * na::Vector3::z() - forward.normalize()).norm() < 1e-12,
+            "OpenCV camera looks along +Z"
+        );
+        assert!(
+            look_at(&up, &up, CameraConvention::Ros)
+                .expect("nonzero")
+                .1
+                .is_some(),
+            "parallel up is replaced"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Conversions between the unit quaternion and the other rotation parameterizations.
+
+use nalgebra as na;
+
+/// Modified Rodrigues parameters `σ = v / (1 + w)` of the quaternion.
+///
+/// The quaternion is flipped to the `w >= 0` hemisphere first, so the result is always the
+/// short-rotation set with `|σ| <= 1`.
+pub fn quat_to_mrp(quat: &na::UnitQuaternion<f64>) -> na::Vector3<f64> {
+    let quat = if quat.w < 0.0 {
+        -quat.into_inner()
+    } else {
+        quat.into_inner()
+    };
+    quat.imag() / (1.0 + quat.w)
+}
+
+/// Inverse of [`quat_to_mrp`]; any `σ` is accepted, including the long-rotation set `|σ| > 1`.
+pub fn mrp_to_quat(mrp: &na::Vector3<f64>) -> na::UnitQuaternion<f64> {
+    let norm_sq = mrp.norm_squared();
+    let vector = mrp * (2.0 / (1.0 + norm_sq));
+    na::UnitQuaternion::from_quaternion(na::Quaternion::from_parts(
+        (1.0 - norm_sq) / (1.0 + norm_sq),
+        vector,
+    ))
+}
+
+/// Below this `|σ|²` the shadow set is too large to be meaningful.
+const MRP_SHADOW_EPSILON: f64 = 1e-12;
+
+/// The shadow set `σˢ = -σ / |σ|²`, describing the same attitude by the other way around.
+///
+/// `None` at the identity, where the shadow set is the 360° rotation and diverges.
+pub fn mrp_shadow(mrp: &na::Vector3<f64>) -> Option<na::Vector3<f64>> {
+    let norm_sq = mrp.norm_squared();
+    (norm_sq >= MRP_SHADOW_EPSILON).then(|| -mrp / norm_sq)
+}
+
+/// Gibbs vector (classical Rodrigues parameters) `g = v / w`, infinite at 180°.
+pub fn quat_to_gibbs(quat: &na::UnitQuaternion<f64>) -> na::Vector3<f64> {
+    quat.imag() / quat.w
+}
+
+/// Inverse of [`quat_to_gibbs`].
+pub fn gibbs_to_quat(gibbs: &na::Vector3<f64>) -> na::UnitQuaternion<f64> {
+    na::UnitQuaternion::from_quaternion(na::Quaternion::from_parts(1.0, *gibbs))
+}
+
+/// Rotation from the continuous 6D representation: the first two columns of `R`,
+/// orthonormalized by Gram-Schmidt.
+pub fn rotation_from_6d(
+    first: &na::Vector3<f64>,
+    second: &na::Vector3<f64>,
+) -> anyhow::Result<na::Rotation3<f64>> {
+    let Some(b1) = first.try_normalize(f64::EPSILON) else {
+        anyhow::bail!("first column is zero");
+    };
+    let Some(b2) = (second - b1 * b1.dot(second)).try_normalize(f64::EPSILON) else {
+        anyhow::bail!("columns are collinear");
+    };
+    Ok(na::Rotation3::from_matrix_unchecked(
+        na::Matrix3::from_columns(&[b1, b2, b1.cross(&b2)]),
+    ))
+}
+
+/// Closest rotation to an arbitrary 3x3 matrix in the Frobenius norm, `U diag(1, 1, det(UVᵀ)) Vᵀ`.
+pub fn rotation_from_svd(matrix: &na::Matrix3<f64>) -> na::Rotation3<f64> {
+    let svd = matrix.svd(true, true);
+    let u = svd.u.expect("u was requested");
+    let v_t = svd.v_t.expect("v_t was requested");
+    let correction = na::Matrix3::from_diagonal(&na::Vector3::new(
+        1.0,
+        1.0,
+        (u * v_t).determinant().signum(),
+    ));
+    na::Rotation3::from_matrix_unchecked(u * correction * v_t)
+}
+
+/// Splits `quat` into `swing * twist`, where `twist` rotates about `axis` and `swing` about an
+/// axis perpendicular to it.
+///
+/// When `quat` is a half turn about an axis perpendicular to `axis`, the twist is undefined and
+/// taken as the identity.
+pub fn swing_twist(
+    quat: &na::UnitQuaternion<f64>,
+    axis: &na::UnitVector3<f64>,
+) -> (na::UnitQuaternion<f64>, na::UnitQuaternion<f64>) {
+    let projection = axis.into_inner() * quat.imag().dot(axis);
+    let twist =
+        na::UnitQuaternion::try_new(na::Quaternion::from_parts(quat.w, projection), f64::EPSILON)
+            .unwrap_or_else(na::UnitQuaternion::identity);
+    (quat * twist.inverse(), twist)
+}
+
+/// Signed angle of `quat` about `axis` in `(-π, π]`, assuming `quat` is a pure twist about it.
+pub fn twist_angle(quat: &na::UnitQuaternion<f64>, axis: &na::UnitVector3<f64>) -> f64 {
+    let angle = 2.0 * quat.imag().dot(axis).atan2(quat.w);
+    if angle > std::f64::consts::PI {
+        angle - std::f64::consts::TAU
+    } else if angle <= -std::f64::consts::PI {
+        angle + std::f64::consts::TAU
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mrp_roundtrip() {
+        let quat = na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5);
+        let mrp = quat_to_mrp(&quat);
+        assert!(mrp.norm() <= 1.0, "principal set must have |σ| <= 1");
+        assert!(mrp_to_quat(&mrp).angle_to(&quat) < 1e-12, "MRP roundtrip");
+        assert!(
+            mrp_to_quat(&mrp_shadow(&mrp).expect("not the identity")).angle_to(&quat) < 1e-12,
+            "shadow set is the same attitude"
+        );
+        assert_eq!(
+            mrp_shadow(&na::Vector3::zeros()),
+            None,
+            "no shadow at the identity"
+        );
+    }
+
+    #[test]
+    fn test_6d_and_svd() {
+        let rot = na::Rotation3::from_euler_angles(0.3, -1.2, 2.5);
+        let noisy = rot.matrix() + na::Matrix3::from_element(0.01);
+        let from_6d =
+            rotation_from_6d(&noisy.column(0).into_owned(), &noisy.column(1).into_owned())
+                .expect("valid 6D input");
+        assert!(from_6d.angle_to(&rot) < 0.05, "Gram-Schmidt stays close");
+        assert!(
+            rotation_from_svd(&noisy).angle_to(&rot) < 0.05,
+            "SVD projection stays close"
+        );
+        assert!(
+            rotation_from_svd(rot.matrix()).angle_to(&rot) < 1e-12,
+            "SVD projection keeps a valid rotation"
+        );
+        assert!(
+            rotation_from_6d(&na::Vector3::x(), &na::Vector3::x()).is_err(),
+            "collinear columns are rejected"
+        );
+    }
+
+    #[test]
+    fn test_swing_twist() {
+        let axis = na::UnitVector3::new_normalize(na::Vector3::new(0.2, 1.0, -0.3));
+        let quat = na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5);
+        let (swing, twist) = swing_twist(&quat, &axis);
+        assert!((swing * twist).angle_to(&quat) < 1e-12, "recomposes");
+        assert!(
+            swing.imag().dot(&axis).abs() < 1e-12,
+            "swing axis is perpendicular to the twist axis"
+        );
+        assert!(
+            twist.imag().cross(&axis).norm() < 1e-12,
+            "twist axis is the twist axis"
+        );
+        let pure_twist = na::UnitQuaternion::from_axis_angle(&axis, -2.0);
+        assert!(
+            (twist_angle(&pure_twist, &axis) + 2.0).abs() < 1e-12,
+            "signed twist angle"
+        );
+    }
+
+    #[test]
+    fn test_gibbs_roundtrip() {
+        let quat = na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5);
+        assert!(
+            gibbs_to_quat(&quat_to_gibbs(&quat)).angle_to(&quat) < 1e-12,
+            "Gibbs roundtrip"
+        );
+        let half_turn =
+            na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), std::f64::consts::PI);
+        assert!(
+            quat_to_gibbs(&half_turn).x.abs() > 1e12,
+            "Gibbs diverges at 180°"
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Serial chains from Denavit-Hartenberg parameter tables.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum DhConvention {
+    /// `Rz(θ) Tz(d) Tx(a) Rx(α)`, with `a` and `α` describing the link after the joint.
+    Standard,
+    /// Craig's `Rx(α) Tx(a) Rz(θ) Tz(d)`, with `a` and `α` describing the link before the joint.
+    Modified,
+}
+
+/// One row of the table; angles in radians.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DhRow {
+    pub theta: f64,
+    pub d: f64,
+    pub a: f64,
+    pub alpha: f64,
+}
+
+/// Pose of a link frame in the frame of the previous link.
+pub fn link_transform(convention: DhConvention, row: &DhRow) -> na::Isometry3<f64> {
+    let rot_z = na::Isometry3::rotation(na::Vector3::z() * row.theta);
+    let trans_z = na::Isometry3::translation(0.0, 0.0, row.d);
+    let trans_x = na::Isometry3::translation(row.a, 0.0, 0.0);
+    let rot_x = na::Isometry3::rotation(na::Vector3::x() * row.alpha);
+    match convention {
+        DhConvention::Standard => rot_z * trans_z * trans_x * rot_x,
+        DhConvention::Modified => rot_x * trans_x * rot_z * trans_z,
+    }
+}
+
+/// Cumulative pose of every link frame in the base frame; the last one is the end effector.
+pub fn chain(convention: DhConvention, rows: &[DhRow]) -> Vec<na::Isometry3<f64>> {
+    rows.iter()
+        .scan(na::Isometry3::identity(), |pose, row| {
+            *pose *= link_transform(convention, row);
+            Some(*pose)
+        })
+        .collect()
+}
+
+fn format_pose(pose: &na::Isometry3<f64>) -> String {
+    let (rotation, translation) = (pose.rotation, pose.translation.vector);
+    format!(
+        "[{:.4}, {:.4}, {:.4}, {:.4}] + [{:.4}, {:.4}, {:.4}]",
+        rotation.w, rotation.i, rotation.j, rotation.k, translation.x, translation.y, translation.z
+    )
+}
+
+fn angle_value(angle: &mut f64, degrees: bool) -> egui::DragValue<'_> {
+    if degrees {
+        egui::DragValue::from_get_set(move |value| {
+            if let Some(value) = value {
+                *angle = value.to_radians();
+            }
+            angle.to_degrees()
+        })
+        .speed(1.0)
+        .suffix("°")
+    } else {
+        egui::DragValue::new(angle).speed(0.01)
+    }
+}
+
+/// State of the DH table panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct DhTool {
+    convention: DhConvention,
+    rows: Vec<DhRow>,
+    degrees: bool,
+    /// Rows of `θ d a α` pasted from or exported to a spreadsheet.
+    table_text: String,
+    #[serde(skip)]
+    import_error: Option<String>,
+}
+
+impl Default for DhTool {
+    fn default() -> Self {
+        Self {
+            convention: DhConvention::Standard,
+            rows: vec![
+                DhRow {
+                    theta: 0.0,
+                    d: 0.0,
+                    a: 1.0,
+                    alpha: 0.0,
+                },
+                DhRow {
+                    theta: 0.0,
+                    d: 0.0,
+                    a: 1.0,
+                    alpha: 0.0,
+                },
+            ],
+            degrees: true,
+            table_text: String::new(),
+            import_error: None,
+        }
+    }
+}
+
+impl DhTool {
+    /// Returns the link or end-effector pose the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::Isometry3<f64>> {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("convention")
+                .selected_text(format!("{:?}", self.convention))
+                .show_ui(ui, |ui| {
+                    for convention in DhConvention::iter() {
+                        ui.selectable_value(
+                            &mut self.convention,
+                            convention,
+                            format!("{convention:?}"),
+                        );
+                    }
+                });
+            ui.checkbox(&mut self.degrees, "degrees");
+        });
+        let loaded = self.table_ui(ui);
+        self.text_ui(ui);
+        loaded
+    }
+
+    fn table_ui(&mut self, ui: &mut egui::Ui) -> Option<na::Isometry3<f64>> {
+        let (convention, degrees) = (self.convention, self.degrees);
+        let poses = chain(convention, &self.rows);
+        let mut loaded = None;
+        let mut removed = None;
+        egui::Grid::new("dh_table").striped(true).show(ui, |ui| {
+            for header in ["#", "θ", "d", "a", "α", "link", "cumulative", ""] {
+                ui.label(egui::RichText::new(header).strong());
+            }
+            ui.end_row();
+            for (index, (row, pose)) in self.rows.iter_mut().zip(&poses).enumerate() {
+                ui.label(format!("{}", index + 1));
+                ui.add(angle_value(&mut row.theta, degrees));
+                ui.add(egui::DragValue::new(&mut row.d).speed(0.01));
+                ui.add(egui::DragValue::new(&mut row.a).speed(0.01));
+                ui.add(angle_value(&mut row.alpha, degrees));
+                let link = link_transform(convention, row);
+                for (pose, hover) in [
+                    (link, "pose in the previous link's frame"),
+                    (*pose, "pose in the base frame"),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format_pose(&pose));
+                        if ui.small_button("load").on_hover_text(hover).clicked() {
+                            loaded = Some(pose);
+                        }
+                    });
+                }
+                if ui.small_button("✖").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = removed {
+            self.rows.remove(index);
+        }
+        if ui.button("add row").clicked() {
+            self.rows.push(DhRow {
+                theta: 0.0,
+                d: 0.0,
+                a: 0.0,
+                alpha: 0.0,
+            });
+        }
+        if let Some(end_effector) = poses.last() {
+            ui.horizontal(|ui| {
+                ui.label(format!("End effector: {}", format_pose(end_effector)));
+                if ui.small_button("load").clicked() {
+                    loaded = Some(*end_effector);
+                }
+            });
+        }
+        loaded
+    }
+
+    fn text_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("import rows").clicked() {
+                let nums = crate::split_numbers(&self.table_text);
+                let scale = if self.degrees { 1f64.to_radians() } else { 1.0 };
+                self.import_error = (!nums.len().is_multiple_of(4))
+                    .then(|| format!("{} numbers is not a multiple of 4", nums.len()));
+                if self.import_error.is_none() {
+                    #[expect(clippy::indexing_slicing, reason = "chunks of exactly four")]
+                    let rows = nums
+                        .chunks_exact(4)
+                        .map(|c| DhRow {
+                            theta: c[0] * scale,
+                            d: c[1],
+                            a: c[2],
+                            alpha: c[3] * scale,
+                        })
+                        .collect();
+                    self.rows = rows;
+                }
+            }
+            if ui.button("export rows").clicked() {
+                let scale = if self.degrees { 1f64.to_degrees() } else { 1.0 };
+                self.table_text = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        format!(
+                            "{:.9}\t{:.9}\t{:.9}\t{:.9}",
+                            row.theta * scale,
+                            row.d,
+                            row.a,
+                            row.alpha * scale
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        });
+        if let Some(e) = &self.import_error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        ui.add(
+            egui::TextEdit::multiline(&mut self.table_text)
+                .desired_width(f32::INFINITY)
+                .desired_rows(4)
+                .hint_text("one row per line: θ d a α, e.g. pasted from a spreadsheet"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planar_arm() {
+        let (theta1, theta2, l1, l2): (f64, f64, f64, f64) = (0.4, -1.1, 1.5, 0.7);
+        let row = |theta, a| DhRow {
+            theta,
+            d: 0.0,
+            a,
+            alpha: 0.0,
+        };
+        let expected = na::Vector3::new(
+            l1 * theta1.cos() + l2 * (theta1 + theta2).cos(),
+            l1 * theta1.sin() + l2 * (theta1 + theta2).sin(),
+            0.0,
+        );
+        let standard = chain(DhConvention::Standard, &[row(theta1, l1), row(theta2, l2)]);
+        // Modified parameters carry each link length on the next row, plus a tool frame.
+        let modified = chain(
+            DhConvention::Modified,
+            &[row(theta1, 0.0), row(theta2, l1), row(0.0, l2)],
+        );
+        for (name, poses) in [("standard", standard), ("modified", modified)] {
+            let end = poses.last().expect("nonempty chain");
+            assert!(
+                (end.translation.vector - expected).norm() < 1e-12,
+                "{name} end-effector position"
+            );
+            assert!(
+                (end.rotation.angle() - (theta1 + theta2).abs()).abs() < 1e-12,
+                "{name} end-effector orientation"
+            );
+        }
+    }
+}
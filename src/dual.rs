@@ -0,0 +1,205 @@
+//! Unit dual quaternions `q = r + ε d` with `d = ½ t r`, and screw interpolation between poses.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+use crate::raw::RawStringType;
+
+/// Dot product of the two real parts below which screw interpolation has no unique path (rotations 180° apart).
+const SCLERP_EPSILON: f64 = 1e-9;
+
+pub fn from_isometry(transform: &na::Isometry3<f64>) -> na::UnitDualQuaternion<f64> {
+    na::UnitDualQuaternion::from_isometry(transform)
+}
+
+/// The pose of a dual quaternion given as real `[w, x, y, z]` and dual `[w, x, y, z]`,
+/// normalized by the real part. The dual part's component along the real part, which a unit
+/// dual quaternion cannot have, is ignored.
+///
+/// # Errors
+/// When the real part is zero.
+pub fn to_isometry(real: [f64; 4], dual: [f64; 4]) -> anyhow::Result<na::Isometry3<f64>> {
+    let [w, x, y, z] = real;
+    let real = na::Quaternion::new(w, x, y, z);
+    let [w, x, y, z] = dual;
+    let dual = na::Quaternion::new(w, x, y, z);
+    if real.norm() < f64::EPSILON {
+        anyhow::bail!("the real part must be nonzero");
+    }
+    Ok(
+        na::UnitDualQuaternion::new_normalize(na::DualQuaternion::from_real_and_dual(real, dual))
+            .to_isometry(),
+    )
+}
+
+/// Real and dual parts, each `[w, x, y, z]`.
+pub fn wxyz(dual_quat: &na::UnitDualQuaternion<f64>) -> ([f64; 4], [f64; 4]) {
+    let parts = |q: &na::Quaternion<f64>| [q.w, q.i, q.j, q.k];
+    (parts(&dual_quat.real), parts(&dual_quat.dual))
+}
+
+/// Screw linear interpolation: constant rotation and translation speed along one screw axis.
+///
+/// `None` when the rotations are 180° apart and the screw is not unique.
+pub fn sclerp(
+    start: &na::Isometry3<f64>,
+    end: &na::Isometry3<f64>,
+    t: f64,
+) -> Option<na::Isometry3<f64>> {
+    from_isometry(start)
+        .try_sclerp(&from_isometry(end), t, SCLERP_EPSILON)
+        .map(na::UnitDualQuaternion::to_isometry)
+}
+
+/// A pose typed in a raw layout; layouts without a translation get a zero one.
+fn parse_pose(text: &str, layout: RawStringType) -> anyhow::Result<na::Isometry3<f64>> {
+    let nums = layout.numbers(text)?;
+    let rotation = layout.parse(&nums)?;
+    let translation = layout.translation(&nums).unwrap_or_else(na::Vector3::zeros);
+    Ok(na::Isometry3::from_parts(translation.into(), rotation))
+}
+
+pub fn format_dual_quat(dual_quat: &na::UnitDualQuaternion<f64>) -> String {
+    let ([rw, rx, ry, rz], [dw, dx, dy, dz]) = wxyz(dual_quat);
+    format!("[{rw:.4}, {rx:.4}, {ry:.4}, {rz:.4}] + ε[{dw:.4}, {dx:.4}, {dy:.4}, {dz:.4}]")
+}
+
+/// State of the screw interpolation panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SclerpTool {
+    start: String,
+    end: String,
+    layout: RawStringType,
+    t: f64,
+}
+
+impl Default for SclerpTool {
+    fn default() -> Self {
+        let layout = RawStringType::DualQuaternionWXYZ;
+        Self {
+            start: layout.format(&na::UnitQuaternion::identity()),
+            end: layout.format_transform(
+                &na::UnitQuaternion::from_axis_angle(&na::Vector3::z_axis(), 1.0),
+                &na::Vector3::new(1.0, 0.0, 0.0),
+            ),
+            layout,
+            t: 0.5,
+        }
+    }
+}
+
+impl SclerpTool {
+    /// Returns the interpolated pose the user chose to load into the main views.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        current: &na::Isometry3<f64>,
+    ) -> Option<na::Isometry3<f64>> {
+        egui::ComboBox::from_label("pose layout")
+            .selected_text(format!("{:?}", self.layout))
+            .show_ui(ui, |ui| {
+                for string_type in RawStringType::iter() {
+                    ui.selectable_value(&mut self.layout, string_type, format!("{string_type:?}"));
+                }
+            });
+        for (name, text) in [("start", &mut self.start), ("end", &mut self.end)] {
+            ui.horizontal(|ui| {
+                ui.label(format!("{name}:"));
+                if ui.small_button("current").clicked() {
+                    *text = self
+                        .layout
+                        .format_transform(&current.rotation, &current.translation.vector);
+                }
+            });
+            ui.add(egui::TextEdit::multiline(text).desired_width(f32::INFINITY));
+        }
+        ui.add(egui::Slider::new(&mut self.t, 0.0..=1.0).text("t"));
+
+        let poses = parse_pose(&self.start, self.layout)
+            .and_then(|start| Ok((start, parse_pose(&self.end, self.layout)?)));
+        let (start, end) = match poses {
+            Ok(poses) => poses,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+        let Some(pose) = sclerp(&start, &end, self.t) else {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "The rotations are 180° apart: the screw path is not unique",
+            );
+            return None;
+        };
+        ui.label(format_dual_quat(&from_isometry(&pose)));
+        let mut loaded = None;
+        ui.horizontal(|ui| {
+            let (rotation, translation) = (pose.rotation, pose.translation.vector);
+            ui.label(format!(
+                "[{:.4}, {:.4}, {:.4}, {:.4}] + [{:.4}, {:.4}, {:.4}]",
+                rotation.w,
+                rotation.i,
+                rotation.j,
+                rotation.k,
+                translation.x,
+                translation.y,
+                translation.z
+            ));
+            if ui.small_button("load").clicked() {
+                loaded = Some(pose);
+            }
+        });
+        loaded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dual_quaternion_roundtrip() {
+        let pose = na::Isometry3::new(
+            na::Vector3::new(1.0, -2.0, 0.5),
+            na::Vector3::new(0.3, 0.2, -1.1),
+        );
+        let (real, dual) = wxyz(&from_isometry(&pose));
+        let scaled = |q: [f64; 4]| q.map(|x| x * 3.0);
+        let recovered = to_isometry(scaled(real), scaled(dual)).expect("nonzero real part");
+        assert!(
+            (recovered.to_homogeneous() - pose.to_homogeneous()).norm() < 1e-12,
+            "pose survives scaling"
+        );
+        for layout in [
+            RawStringType::DualQuaternionWXYZ,
+            RawStringType::DualQuaternionXYZW,
+        ] {
+            let text = layout.format_transform(&pose.rotation, &pose.translation.vector);
+            let parsed = parse_pose(&text, layout).expect("formatted pose parses");
+            assert!(
+                (parsed.to_homogeneous() - pose.to_homogeneous()).norm() < 1e-8,
+                "{layout:?} roundtrip"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sclerp_follows_screw() {
+        // A quarter turn about Z combined with a climb along Z is a pure screw motion.
+        let start = na::Isometry3::identity();
+        let end = na::Isometry3::new(
+            na::Vector3::new(0.0, 0.0, 2.0),
+            na::Vector3::z() * std::f64::consts::FRAC_PI_2,
+        );
+        let middle = sclerp(&start, &end, 0.5).expect("unique screw");
+        let expected = na::Isometry3::new(
+            na::Vector3::new(0.0, 0.0, 1.0),
+            na::Vector3::z() * std::f64::consts::FRAC_PI_4,
+        );
+        assert!(
+            (middle.to_homogeneous() - expected.to_homogeneous()).norm() < 1e-12,
+            "halfway along the screw"
+        );
+    }
+}
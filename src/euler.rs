@@ -0,0 +1,263 @@
+//! Euler angles for all twelve axis sequences, intrinsic or extrinsic, with gimbal-lock
+//! diagnostics.
+
+use nalgebra as na;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/// Distance in radians from the singular middle angle below which the first and third angles
+/// can no longer be told apart, and one of them is fixed to zero.
+const LOCK_EPSILON: f64 = 1e-7;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+#[expect(
+    clippy::upper_case_acronyms,
+    reason = "axis letters read as the sequence"
+)]
+pub enum EulerSequence {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+}
+
+impl EulerSequence {
+    /// Axis indices in the order the angles are listed.
+    pub fn axes(self) -> [usize; 3] {
+        match self {
+            Self::XYZ => [0, 1, 2],
+            Self::XZY => [0, 2, 1],
+            Self::YXZ => [1, 0, 2],
+            Self::YZX => [1, 2, 0],
+            Self::ZXY => [2, 0, 1],
+            Self::ZYX => [2, 1, 0],
+            Self::XYX => [0, 1, 0],
+            Self::XZX => [0, 2, 0],
+            Self::YXY => [1, 0, 1],
+            Self::YZY => [1, 2, 1],
+            Self::ZXZ => [2, 0, 2],
+            Self::ZYZ => [2, 1, 2],
+        }
+    }
+
+    /// Proper Euler sequences repeat the first axis; the others are Tait-Bryan sequences.
+    pub fn is_proper(self) -> bool {
+        let [first, _, third] = self.axes();
+        first == third
+    }
+
+    /// The middle angle at which the first and third axes line up.
+    pub fn singular_middle_angles(self) -> &'static str {
+        if self.is_proper() {
+            "0° or 180°"
+        } else {
+            "±90°"
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum EulerFrame {
+    /// Each rotation is about an axis of the already rotated frame: `R = R₁(a₁) R₂(a₂) R₃(a₃)`.
+    Intrinsic,
+    /// Each rotation is about a fixed world axis: `R = R₃(a₃) R₂(a₂) R₁(a₁)`.
+    Extrinsic,
+}
+
+pub fn axis_name(axis: usize) -> &'static str {
+    match axis {
+        0 => "X",
+        1 => "Y",
+        _ => "Z",
+    }
+}
+
+fn imag(quat: &na::UnitQuaternion<f64>, axis: usize) -> f64 {
+    match axis {
+        0 => quat.i,
+        1 => quat.j,
+        _ => quat.k,
+    }
+}
+
+fn wrap(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(TAU);
+    if wrapped > PI { wrapped - TAU } else { wrapped }
+}
+
+/// `angles` are listed in sequence order, in radians.
+pub fn to_quat(
+    sequence: EulerSequence,
+    frame: EulerFrame,
+    angles: [f64; 3],
+) -> na::UnitQuaternion<f64> {
+    let [first, second, third] = sequence
+        .axes()
+        .map(|axis| na::Unit::new_unchecked(na::Vector3::ith(axis, 1.0)))
+        .into_iter()
+        .zip(angles)
+        .map(|(axis, angle)| na::UnitQuaternion::from_axis_angle(&axis, angle))
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("three rotations");
+    match frame {
+        EulerFrame::Intrinsic => first * second * third,
+        EulerFrame::Extrinsic => third * second * first,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EulerSolution {
+    /// In sequence order, in radians, each in `(-π, π]`.
+    pub angles: [f64; 3],
+    /// The other angle triple describing the same rotation.
+    pub alternate: [f64; 3],
+    /// How far the middle angle is from its singular value, in radians.
+    pub lock_distance: f64,
+    /// At gimbal lock the third angle is fixed to zero, and the first carries the whole turn.
+    pub locked: bool,
+}
+
+/// Quaternion to Euler angles with the direct method of Bernardes and Viollet (2022).
+pub fn from_quat(
+    sequence: EulerSequence,
+    frame: EulerFrame,
+    quat: &na::UnitQuaternion<f64>,
+) -> EulerSolution {
+    // An intrinsic sequence is the reversed extrinsic one with the angles reversed.
+    let [i, j, last] = match frame {
+        EulerFrame::Extrinsic => sequence.axes(),
+        EulerFrame::Intrinsic => {
+            let [first, second, third] = sequence.axes();
+            [third, second, first]
+        }
+    };
+    let proper = i == last;
+    let k = if proper { 3 - i - j } else { last };
+    // Parity of the permutation (i, j, k).
+    let sign = if (i + 1) % 3 == j { 1.0 } else { -1.0 };
+
+    let w = quat.w;
+    let (a, b, c, d) = if proper {
+        (w, imag(quat, i), imag(quat, j), imag(quat, k) * sign)
+    } else {
+        (
+            w - imag(quat, j),
+            imag(quat, i) + imag(quat, k) * sign,
+            imag(quat, j) + w,
+            imag(quat, k) * sign - imag(quat, i),
+        )
+    };
+    let mut middle = 2.0 * c.hypot(d).atan2(a.hypot(b));
+    let half_sum = b.atan2(a);
+    let half_diff = d.atan2(c);
+    let lock_distance = middle.min(PI - middle);
+    let locked = lock_distance < LOCK_EPSILON;
+    // The angle fixed at gimbal lock is the third one in the listed sequence order.
+    let zero_first = frame == EulerFrame::Intrinsic;
+    let (first, mut third) = if !locked {
+        (half_sum - half_diff, half_sum + half_diff)
+    } else if middle < FRAC_PI_2 {
+        // Only first + third is determined.
+        if zero_first {
+            (0.0, 2.0 * half_sum)
+        } else {
+            (2.0 * half_sum, 0.0)
+        }
+    } else if zero_first {
+        // Only third - first is determined.
+        (0.0, 2.0 * half_diff)
+    } else {
+        (-2.0 * half_diff, 0.0)
+    };
+    if !proper {
+        third *= sign;
+        middle -= FRAC_PI_2;
+    }
+
+    let extrinsic = [first, middle, third];
+    let alternate = [
+        first + PI,
+        if proper { -middle } else { PI - middle },
+        third + PI,
+    ];
+    let order = |angles: [f64; 3]| {
+        let [first, middle, third] = angles.map(wrap);
+        match frame {
+            EulerFrame::Extrinsic => [first, middle, third],
+            EulerFrame::Intrinsic => [third, middle, first],
+        }
+    };
+    EulerSolution {
+        angles: order(extrinsic),
+        alternate: order(alternate),
+        lock_distance,
+        locked,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator as _;
+
+    #[test]
+    fn test_roundtrip_all_sequences() {
+        let quats = [
+            na::UnitQuaternion::from_euler_angles(0.3, -1.2, 2.5),
+            na::UnitQuaternion::from_euler_angles(-2.9, 0.1, -0.7),
+        ];
+        // nalgebra's roll, pitch, yaw are intrinsic ZYX listed backwards.
+        let zyx = from_quat(EulerSequence::ZYX, EulerFrame::Intrinsic, &quats[0]).angles;
+        assert!(
+            (na::Vector3::from(zyx) - na::Vector3::new(2.5, -1.2, 0.3)).norm() < 1e-12,
+            "yaw, pitch, roll"
+        );
+        for sequence in EulerSequence::iter() {
+            for frame in EulerFrame::iter() {
+                for quat in &quats {
+                    let solution = from_quat(sequence, frame, quat);
+                    assert!(
+                        to_quat(sequence, frame, solution.angles).angle_to(quat) < 1e-9,
+                        "{frame:?} {sequence:?} roundtrip"
+                    );
+                    assert!(
+                        to_quat(sequence, frame, solution.alternate).angle_to(quat) < 1e-9,
+                        "{frame:?} {sequence:?} alternate solution"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gimbal_lock() {
+        for sequence in EulerSequence::iter() {
+            for frame in EulerFrame::iter() {
+                let middle = if sequence.is_proper() { PI } else { FRAC_PI_2 };
+                let quat = to_quat(sequence, frame, [0.4, middle, 0.3]);
+                let solution = from_quat(sequence, frame, &quat);
+                assert!(solution.locked, "{frame:?} {sequence:?} is locked");
+                assert!(
+                    solution.angles[2].abs() < 1e-12,
+                    "{frame:?} {sequence:?} fixes the third angle"
+                );
+                assert!(
+                    to_quat(sequence, frame, solution.angles).angle_to(&quat) < 1e-9,
+                    "{frame:?} {sequence:?} locked roundtrip"
+                );
+            }
+        }
+    }
+}
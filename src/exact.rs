@@ -0,0 +1,154 @@
+//! Closed forms of values common in rotations by multiples of 15°, such as `√2/2` for 0.7071,
+//! shown next to the decimals. Every symbol is also a valid field expression.
+
+use std::f64::consts::PI;
+
+/// Closeness required of a field that holds an expression rather than rounded decimals.
+const EXPRESSION_TOLERANCE: f64 = 1e-9;
+
+/// Non-integer sines, cosines and tangents of multiples of 7.5°, which make up the quaternions,
+/// matrices and Gibbs vectors of rotations by multiples of 15° and the MRPs of multiples of 30°,
+/// and `√6/3` and `√6/6`.
+fn constants() -> [(&'static str, f64); 24] {
+    let (r2, r3, r6) = (2f64.sqrt(), 3f64.sqrt(), 6f64.sqrt());
+    let (r2_plus_r3, r2_minus_r3) = ((2.0 + r3).sqrt(), (2.0 - r3).sqrt());
+    [
+        ("1/2", 0.5),
+        ("√2", r2),
+        ("√2/2", r2 / 2.0),
+        ("√2-1", r2 - 1.0),
+        ("√2+1", r2 + 1.0),
+        ("√3", r3),
+        ("√3/2", r3 / 2.0),
+        ("√3/3", r3 / 3.0),
+        ("2-√3", 2.0 - r3),
+        ("2+√3", 2.0 + r3),
+        ("(√6-√2)/4", (r6 - r2) / 4.0),
+        ("(√6+√2)/4", (r6 + r2) / 4.0),
+        ("√(2-√2)/2", (2.0 - r2).sqrt() / 2.0),
+        ("√(2+√2)/2", (2.0 + r2).sqrt() / 2.0),
+        ("√6/3", r6 / 3.0),
+        ("√6/6", r6 / 6.0),
+        // cos and sin of 7.5° and 37.5°, the others of 52.5° and 82.5°
+        ("√(2+√(2+√3))/2", (2.0 + r2_plus_r3).sqrt() / 2.0),
+        ("√(2-√(2+√3))/2", (2.0 - r2_plus_r3).sqrt() / 2.0),
+        ("√(2+√(2-√3))/2", (2.0 + r2_minus_r3).sqrt() / 2.0),
+        ("√(2-√(2-√3))/2", (2.0 - r2_minus_r3).sqrt() / 2.0),
+        // tan of 7.5°, 37.5°, 52.5° and 82.5°
+        ("√6-√3+√2-2", r6 - r3 + r2 - 2.0),
+        ("√6+√3-√2-2", r6 + r3 - r2 - 2.0),
+        ("√6-√3-√2+2", r6 - r3 - r2 + 2.0),
+        ("√6+√3+√2+2", r6 + r3 + r2 + 2.0),
+    ]
+}
+
+/// `kπ/12` in lowest terms, such as `π/4` or `5π/6`.
+fn pi_multiple(twelfths: u32) -> String {
+    let divisor = [1, 2, 3, 4, 6, 12]
+        .into_iter()
+        .find(|divisor| twelfths.is_multiple_of(12 / divisor))
+        .unwrap_or(12);
+    let numerator = twelfths * divisor / 12;
+    match (numerator, divisor) {
+        (1, 1) => "π".to_owned(),
+        (_, 1) => format!("{numerator}π"),
+        (1, _) => format!("π/{divisor}"),
+        _ => format!("{numerator}π/{divisor}"),
+    }
+}
+
+/// How far the value of `text` may be from a closed form: half its last printed digit, at most
+/// that of the four decimals the views print.
+fn tolerance(text: &str) -> f64 {
+    let text = text.trim();
+    if text.parse::<f64>().is_err() || text.contains(['e', 'E']) {
+        return EXPRESSION_TOLERANCE;
+    }
+    let decimals = text
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    0.5 * 10f64.powi(-i32::try_from(decimals.max(4)).unwrap_or(i32::MAX))
+}
+
+/// The closed form of `value`, if it is within `tolerance` of one.
+fn closed_form(value: f64, tolerance: f64, angle: bool) -> Option<String> {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let magnitude = value.abs();
+    let symbol = if angle {
+        let twelfths = (magnitude / (PI / 12.0)).round();
+        let close = (magnitude - twelfths * PI / 12.0).abs() <= tolerance;
+        (close && (1.0..=24.0).contains(&twelfths)).then(|| pi_multiple(twelfths as u32))
+    } else {
+        constants()
+            .into_iter()
+            .find(|(_, constant)| (magnitude - constant).abs() <= tolerance)
+            .map(|(symbol, _)| symbol.to_owned())
+    }?;
+    Some(format!("{sign}{symbol}"))
+}
+
+/// The closed form of a field's value. Angles in radians are matched to multiples of `π/12` up
+/// to `2π`; degree fields print those exactly and need no symbol.
+pub fn symbol(text: &str, angle: bool) -> Option<String> {
+    let value = crate::expr::eval(text).ok()?;
+    closed_form(value, tolerance(text), angle)
+}
+
+/// The closed form of a field's value below it, if it has one.
+pub fn label(ui: &mut egui::Ui, text: &str, angle: bool) {
+    if let Some(symbol) = symbol(text, angle) {
+        ui.label(egui::RichText::new(symbol).weak());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol() {
+        assert_eq!(symbol("0.7071", false).as_deref(), Some("√2/2"), "rounded");
+        assert_eq!(
+            symbol("-0.8660", false).as_deref(),
+            Some("-√3/2"),
+            "negative"
+        );
+        assert_eq!(symbol("1.5708", true).as_deref(), Some("π/2"), "angle");
+        assert_eq!(symbol("2.618", true).as_deref(), Some("5π/6"), "angle");
+        assert_eq!(symbol("0.70712", false), None, "more digits than √2/2");
+        assert_eq!(symbol("0.4", false), None, "not close to √2-1");
+        let quat = nalgebra::UnitQuaternion::from_axis_angle(
+            &nalgebra::Vector3::z_axis(),
+            15f64.to_radians(),
+        );
+        assert_eq!(
+            symbol(&format!("{:.4}", quat.w), false).as_deref(),
+            Some("√(2+√(2+√3))/2"),
+            "cos 7.5° of a 15° rotation"
+        );
+        assert_eq!(
+            symbol(&format!("{:.4}", quat.k), false).as_deref(),
+            Some("√(2-√(2+√3))/2"),
+            "sin 7.5° of a 15° rotation"
+        );
+        let mrp = crate::convert::quat_to_mrp(&nalgebra::UnitQuaternion::from_axis_angle(
+            &nalgebra::Vector3::z_axis(),
+            30f64.to_radians(),
+        ));
+        assert_eq!(
+            symbol(&format!("{:.4}", mrp.z), false).as_deref(),
+            Some("√6-√3+√2-2"),
+            "tan 7.5° MRP of a 30° rotation"
+        );
+        for (symbol, value) in constants() {
+            let parsed = crate::expr::eval(symbol).expect(symbol);
+            assert!((parsed - value).abs() < 1e-12, "{symbol} parses back");
+        }
+        for twelfths in 1..=24 {
+            let symbol = pi_multiple(twelfths);
+            let parsed = crate::expr::eval(&symbol).expect("valid expression");
+            let expected = f64::from(twelfths) * PI / 12.0;
+            assert!((parsed - expected).abs() < 1e-12, "{symbol}");
+        }
+    }
+}
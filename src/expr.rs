@@ -0,0 +1,308 @@
+//! Arithmetic expressions in numeric fields, such as `pi/2`, `sqrt(2)/2`, `√3/2` or `cos(30deg)`.
+//!
+//! Angle fields evaluate in their own unit like a calculator in DEG or RAD mode: in a degree
+//! field `sin(30)` is 0.5, `90` is a right angle and `pi/2 rad` is converted to 90.
+
+use std::f64::consts::{E, PI, TAU};
+
+/// How far an expression may be from a synced value and still be kept in its field.
+const KEEP_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Name(name) => write!(f, "{name:?}"),
+            Self::Symbol(symbol) => write!(f, "{symbol:?}"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> anyhow::Result<Vec<Token>> {
+    let number = regex::Regex::new(r"^(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?")
+        .expect("Failed to compile regex");
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if let Some(found) = number.find(rest) {
+            let literal = found.as_str();
+            tokens.push(Token::Number(literal.parse()?));
+            literal.len()
+        } else if c == '°' {
+            tokens.push(Token::Name(c.to_string()));
+            c.len_utf8()
+        } else if c.is_alphabetic() {
+            let name = rest
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+                .unwrap_or_default();
+            tokens.push(Token::Name(name.to_owned()));
+            name.len()
+        } else if rest.starts_with("**") {
+            tokens.push(Token::Symbol('^'));
+            2
+        } else if "+-*/^(),√".contains(c) {
+            tokens.push(Token::Symbol(c));
+            c.len_utf8()
+        } else {
+            anyhow::bail!("unexpected {c:?}");
+        };
+        rest = rest.get(len..).unwrap_or_default().trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser that evaluates as it goes.
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    /// Field units per radian: 1 in radian fields, 180/π in degree fields.
+    unit: f64,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: char) -> anyhow::Result<()> {
+        if !self.eat(symbol) {
+            anyhow::bail!("expected {symbol:?}");
+        }
+        Ok(())
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn sum(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat('+') {
+                value += self.product()?;
+            } else if self.eat('-') {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `unary unit? (('*' | '/')? unary unit?)*`, where a unit scales the product so far, so
+    /// `pi/2 rad` is a right angle, and a missing `*` before a name, `√` or `(` multiplies, so
+    /// `5π/12` reads as written.
+    fn product(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            value *= self.unit_scale();
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                value /= self.unary()?;
+            } else if matches!(self.peek(), Some(Token::Name(_) | Token::Symbol('(' | '√'))) {
+                value *= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `('+' | '-' | '√') unary | power`
+    fn unary(&mut self) -> anyhow::Result<f64> {
+        if self.eat('-') {
+            Ok(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else if self.eat('√') {
+            Ok(self.unary()?.sqrt())
+        } else {
+            self.power()
+        }
+    }
+
+    /// `primary ('^' unary)?`, so `2^-1` works and `-2^2` is -4.
+    fn power(&mut self) -> anyhow::Result<f64> {
+        let base = self.primary()?;
+        if self.eat('^') {
+            Ok(base.powf(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Takes a `deg`, `°` or `rad` suffix and returns its size in field units, or 1 without one.
+    fn unit_scale(&mut self) -> f64 {
+        let scale = match self.peek() {
+            Some(Token::Name(name)) if name == "deg" || name == "°" => PI / 180.0 * self.unit,
+            Some(Token::Name(name)) if name == "rad" => self.unit,
+            _ => return 1.0,
+        };
+        self.next += 1;
+        scale
+    }
+
+    /// A number, a constant, a function call or a parenthesized expression.
+    fn primary(&mut self) -> anyhow::Result<f64> {
+        let token = self.peek().cloned();
+        self.next += 1;
+        match token {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Symbol('(')) => {
+                let value = self.sum()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(Token::Name(name)) if self.peek() == Some(&Token::Symbol('(')) => {
+                self.next += 1;
+                let mut args = vec![self.sum()?];
+                while self.eat(',') {
+                    args.push(self.sum()?);
+                }
+                self.expect(')')?;
+                self.call(&name, &args)
+            }
+            Some(Token::Name(name)) => match name.as_str() {
+                "pi" | "π" => Ok(PI),
+                "tau" | "τ" => Ok(TAU),
+                "e" => Ok(E),
+                _ => anyhow::bail!("unknown name {name:?}"),
+            },
+            Some(token) => anyhow::bail!("unexpected {token}"),
+            None => anyhow::bail!("unexpected end"),
+        }
+    }
+
+    fn call(&self, name: &str, args: &[f64]) -> anyhow::Result<f64> {
+        let unit = self.unit;
+        Ok(match (name, args) {
+            ("atan2", &[y, x]) => y.atan2(x) * unit,
+            ("atan2", _) => anyhow::bail!("atan2 takes two arguments"),
+            (_, &[x]) => match name {
+                "sin" => (x / unit).sin(),
+                "cos" => (x / unit).cos(),
+                "tan" => (x / unit).tan(),
+                "asin" => x.asin() * unit,
+                "acos" => x.acos() * unit,
+                "atan" => x.atan() * unit,
+                "sqrt" => x.sqrt(),
+                "abs" => x.abs(),
+                "exp" => x.exp(),
+                "ln" => x.ln(),
+                "log10" => x.log10(),
+                _ => anyhow::bail!("unknown function {name:?}"),
+            },
+            _ => anyhow::bail!("{name} takes one argument"),
+        })
+    }
+}
+
+fn evaluate(text: &str, degrees: bool) -> anyhow::Result<f64> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        next: 0,
+        unit: if degrees { 180.0 / PI } else { 1.0 },
+    };
+    let value = parser.sum()?;
+    if let Some(token) = parser.peek() {
+        anyhow::bail!("unexpected {token}");
+    }
+    Ok(value)
+}
+
+/// The value of a numeric field, with angles in radians.
+///
+/// # Errors
+/// When the text is not a valid expression.
+pub fn eval(text: &str) -> anyhow::Result<f64> {
+    evaluate(text, false)
+}
+
+/// The value of a numeric field, with angles in degrees.
+///
+/// # Errors
+/// When the text is not a valid expression.
+pub fn eval_degrees(text: &str) -> anyhow::Result<f64> {
+    evaluate(text, true)
+}
+
+fn is_plain_number(text: &str) -> bool {
+    text.trim().parse::<f64>().is_ok()
+}
+
+/// Writes `value` into a field, unless the field holds an expression that already evaluates to
+/// it, so `pi/2` survives syncing the views until the value actually changes.
+pub fn set_field(text: &mut String, value: f64, degrees: bool) {
+    let keep = !is_plain_number(text)
+        && evaluate(text, degrees)
+            .is_ok_and(|kept| (kept - value).abs() <= KEEP_TOLERANCE * value.abs().max(1.0));
+    if !keep {
+        *text = format!("{value:.4}");
+    }
+}
+
+/// The value of an expression next to its field; nothing for plain numbers.
+pub fn hint(ui: &mut egui::Ui, text: &str, degrees: bool) {
+    if is_plain_number(text) {
+        return;
+    }
+    match evaluate(text, degrees) {
+        Ok(value) => {
+            ui.weak(format!("= {value:.6}"));
+        }
+        Err(e) => {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{e}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let cases = [
+            ("pi/2", PI / 2.0),
+            ("sqrt(2)/2", 2f64.sqrt() / 2.0),
+            ("cos(60deg)", 0.5),
+            ("-2^2", -4.0),
+            ("2**-1", 0.5),
+            ("1.5e-3 * 2", 3e-3),
+            ("atan2(1, 1)", PI / 4.0),
+            ("tau - 2 * π", 0.0),
+            ("2 * e", 2.0 * E),
+            ("5π/12", 5.0 * PI / 12.0),
+            ("(√6-√2)/4", (6f64.sqrt() - 2f64.sqrt()) / 4.0),
+        ];
+        for (text, expected) in cases {
+            let value = eval(text).expect(text);
+            assert!((value - expected).abs() < 1e-12, "{text} = {value}");
+        }
+        let value = eval_degrees("sin(30) + pi/2 rad").expect("valid");
+        assert!((value - 90.5).abs() < 1e-12, "degree mode, got {value}");
+        assert!(eval("2 +").is_err(), "incomplete");
+        assert!(eval("foo(1)").is_err(), "unknown function");
+    }
+
+    #[test]
+    fn test_set_field() {
+        let mut text = "sqrt(2)/2".to_owned();
+        set_field(&mut text, std::f64::consts::FRAC_1_SQRT_2, false);
+        assert_eq!(text, "sqrt(2)/2", "same value keeps the expression");
+        set_field(&mut text, 0.5, false);
+        assert_eq!(text, "0.5000", "a new value overwrites it");
+    }
+}
@@ -0,0 +1,472 @@
+//! Node transforms of glTF 2.0 `.gltf` (JSON) files.
+//!
+//! glTF is right-handed with +Y up; the front of an asset faces +Z, so +X points to its left.
+//! Rotations are unit quaternions stored as `[x, y, z, w]`, and `matrix` is a column-major 4x4
+//! that replaces `translation`, `rotation` and `scale` when present.
+
+use anyhow::Context as _;
+use nalgebra as na;
+use serde_json::Value;
+
+pub const CONVENTION: &str = "glTF is right-handed with +Y up and the front of the asset facing +Z \
+     (+X is the asset's left). Quaternions are stored as [x, y, z, w]; matrices are column-major.";
+
+/// A node's local transform as stored in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub name: Option<String>,
+    /// `[x, y, z, w]`.
+    pub rotation: Option<[f64; 4]>,
+    pub translation: Option<[f64; 3]>,
+    pub scale: Option<[f64; 3]>,
+    /// Column-major.
+    pub matrix: Option<[f64; 16]>,
+    pub children: Vec<usize>,
+}
+
+impl Node {
+    /// The local transform as a 4x4 matrix, from `matrix` or from TRS with identity defaults.
+    pub fn local_matrix(&self) -> na::Matrix4<f64> {
+        if let Some(matrix) = &self.matrix {
+            return na::Matrix4::from_column_slice(matrix);
+        }
+        let [x, y, z] = self.translation.unwrap_or_default();
+        let [qx, qy, qz, qw] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [sx, sy, sz] = self.scale.unwrap_or([1.0; 3]);
+        let rotation = na::UnitQuaternion::from_quaternion(na::Quaternion::new(qw, qx, qy, qz));
+        na::Matrix4::new_translation(&na::Vector3::new(x, y, z))
+            * rotation.to_homogeneous()
+            * na::Matrix4::new_nonuniform_scaling(&na::Vector3::new(sx, sy, sz))
+    }
+}
+
+/// Translation, rotation and per-axis scale of a 4x4 affine matrix without shear.
+pub fn decompose(
+    matrix: &na::Matrix4<f64>,
+) -> (na::Vector3<f64>, na::UnitQuaternion<f64>, na::Vector3<f64>) {
+    let linear = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+    let mut scale = na::Vector3::from_fn(|i, _| linear.column(i).norm());
+    if linear.determinant() < 0.0 {
+        // A mirror: fold it into the X scale so the rest is a proper rotation.
+        scale.x = -scale.x;
+    }
+    let unscaled = linear * na::Matrix3::from_diagonal(&scale.map(f64::recip));
+    (
+        matrix.fixed_view::<3, 1>(0, 3).into_owned(),
+        na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(&unscaled)),
+        scale,
+    )
+}
+
+/// The nodes of a glTF document and the JSON they came from, to write edits back into.
+#[derive(Debug, Clone)]
+pub struct Gltf {
+    pub document: Value,
+    pub nodes: Vec<Node>,
+    /// Index of each node's parent.
+    pub parents: Vec<Option<usize>>,
+}
+
+fn floats<const N: usize>(
+    node: &Value,
+    key: &str,
+    index: usize,
+) -> anyhow::Result<Option<[f64; N]>> {
+    let Some(value) = node.get(key) else {
+        return Ok(None);
+    };
+    let nums = value
+        .as_array()
+        .and_then(|array| array.iter().map(Value::as_f64).collect::<Option<Vec<_>>>())
+        .with_context(|| format!("node {index}: {key} is not an array of numbers"))?;
+    let nums = <[f64; N]>::try_from(nums).map_err(|nums| {
+        anyhow::anyhow!("node {index}: {key} has {} numbers, not {N}", nums.len())
+    })?;
+    Ok(Some(nums))
+}
+
+/// Reads the `nodes` of a `.gltf` file.
+///
+/// # Errors
+/// When the text is not JSON, or a node property has the wrong shape.
+pub fn parse(text: &str) -> anyhow::Result<Gltf> {
+    let document: Value = serde_json::from_str(text).context("not a .gltf JSON file")?;
+    let nodes = document
+        .get("nodes")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .enumerate()
+        .map(|(index, node)| {
+            Ok(Node {
+                name: node.get("name").and_then(Value::as_str).map(str::to_owned),
+                rotation: floats(node, "rotation", index)?,
+                translation: floats(node, "translation", index)?,
+                scale: floats(node, "scale", index)?,
+                matrix: floats(node, "matrix", index)?,
+                children: node
+                    .get("children")
+                    .and_then(Value::as_array)
+                    .map_or(&[][..], Vec::as_slice)
+                    .iter()
+                    .filter_map(Value::as_u64)
+                    .filter_map(|child| usize::try_from(child).ok())
+                    .collect(),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mut parents = vec![None; nodes.len()];
+    for (index, node) in nodes.iter().enumerate() {
+        for &child in &node.children {
+            let Some(parent) = parents.get_mut(child) else {
+                anyhow::bail!("node {index}: child {child} does not exist");
+            };
+            *parent = Some(index);
+        }
+    }
+    Ok(Gltf {
+        document,
+        nodes,
+        parents,
+    })
+}
+
+impl Gltf {
+    /// The transform of node `index` in the scene root, or `None` for a cycle or a bad index.
+    pub fn world_matrix(&self, index: usize) -> Option<na::Matrix4<f64>> {
+        let mut matrix = self.nodes.get(index)?.local_matrix();
+        let mut current = index;
+        for _ in 0..self.nodes.len() {
+            let Some(parent) = *self.parents.get(current)? else {
+                return Some(matrix);
+            };
+            matrix = self.nodes.get(parent)?.local_matrix() * matrix;
+            current = parent;
+        }
+        None
+    }
+
+    /// Sets the local rotation of node `index`, keeping its translation and scale. A `matrix` is
+    /// replaced by the equivalent `translation`, `rotation` and `scale`.
+    ///
+    /// # Errors
+    /// When there is no such node.
+    pub fn set_rotation(
+        &mut self,
+        index: usize,
+        rotation: &na::UnitQuaternion<f64>,
+    ) -> anyhow::Result<()> {
+        let node = self
+            .nodes
+            .get_mut(index)
+            .with_context(|| format!("no node {index}"))?;
+        let json = self
+            .document
+            .get_mut("nodes")
+            .and_then(|nodes| nodes.get_mut(index))
+            .and_then(Value::as_object_mut)
+            .with_context(|| format!("no node {index}"))?;
+        if let Some(matrix) = node.matrix.take() {
+            let (translation, _, scale) = decompose(&na::Matrix4::from_column_slice(&matrix));
+            json.remove("matrix");
+            node.translation = Some(translation.into());
+            node.scale = Some(scale.into());
+            json.insert(
+                "translation".to_owned(),
+                serde_json::json!(node.translation),
+            );
+            json.insert("scale".to_owned(), serde_json::json!(node.scale));
+        }
+        let quat = rotation.coords;
+        node.rotation = Some([quat.x, quat.y, quat.z, quat.w]);
+        json.insert("rotation".to_owned(), serde_json::json!(node.rotation));
+        Ok(())
+    }
+
+    /// Sets the local rotation of node `index` so that its rotation in the scene is `rotation`.
+    ///
+    /// # Errors
+    /// When there is no such node or its ancestors form a cycle.
+    pub fn set_world_rotation(
+        &mut self,
+        index: usize,
+        rotation: &na::UnitQuaternion<f64>,
+    ) -> anyhow::Result<()> {
+        let parent = match self.parents.get(index).copied().flatten() {
+            Some(parent) => {
+                let world = self
+                    .world_matrix(parent)
+                    .with_context(|| format!("node {parent} is in a cycle"))?;
+                decompose(&world).1
+            }
+            None => na::UnitQuaternion::identity(),
+        };
+        self.set_rotation(index, &(parent.inverse() * rotation))
+    }
+}
+
+fn format_values(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|x| format!("{x:.4}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// State of the glTF panel. The file itself is not persisted.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct GltfTool {
+    #[serde(skip)]
+    text: String,
+    #[serde(skip)]
+    gltf: Option<anyhow::Result<Gltf>>,
+    #[serde(skip)]
+    selected: Option<usize>,
+    /// Whether the last loaded pose was the world pose, which "write back" then converts to
+    /// the parent's frame.
+    #[serde(skip)]
+    world: bool,
+    /// Where "save copy" writes the edited file.
+    save_path: String,
+    #[serde(skip)]
+    status: Option<anyhow::Result<String>>,
+}
+
+impl GltfTool {
+    /// Returns the local or world pose of a node, to load into the main views. `current` is the
+    /// rotation "write back" stores in the selected node, in the frame of the pose last loaded.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        current: &na::UnitQuaternion<f64>,
+    ) -> Option<na::Isometry3<f64>> {
+        if let Some(dropped) = crate::dropped_text(ui.ctx(), &[".gltf"]) {
+            self.gltf = Some(dropped.and_then(|text| {
+                let gltf = parse(&text);
+                self.text = text;
+                gltf
+            }));
+            self.selected = None;
+        }
+        ui.label(CONVENTION);
+        ui.horizontal(|ui| {
+            if ui.button("parse").clicked() {
+                self.gltf = Some(parse(&self.text));
+                self.selected = None;
+            }
+            ui.label("Paste a .gltf below or drop one onto the window; .glb is not supported.");
+        });
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .desired_rows(4),
+        );
+        let gltf = match &mut self.gltf {
+            Some(Ok(gltf)) => gltf,
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{e:#}"));
+                return None;
+            }
+            None => return None,
+        };
+        let loaded = nodes_ui(ui, gltf, &mut self.selected).map(|(pose, world)| {
+            self.world = world;
+            pose
+        });
+        if let Some(index) = self.selected {
+            ui.horizontal(|ui| {
+                let hover = if self.world {
+                    "store the current rotation as the node's rotation in the scene"
+                } else {
+                    "store the current rotation as the node's local rotation"
+                };
+                if ui.button("write back").on_hover_text(hover).clicked() {
+                    let written = if self.world {
+                        gltf.set_world_rotation(index, current)
+                    } else {
+                        gltf.set_rotation(index, current)
+                    };
+                    self.status = Some(written.and_then(|()| {
+                        self.text = serde_json::to_string_pretty(&gltf.document)?;
+                        Ok(format!("Node {index} rotation updated"))
+                    }));
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.save_path).hint_text("edited.gltf"),
+                    );
+                    if ui.button("save copy").clicked() {
+                        let path = if self.save_path.trim().is_empty() {
+                            "edited.gltf".to_owned()
+                        } else {
+                            self.save_path.clone()
+                        };
+                        self.status = Some(
+                            serde_json::to_string_pretty(&gltf.document)
+                                .map_err(anyhow::Error::from)
+                                .and_then(|json| Ok(std::fs::write(&path, json)?))
+                                .map(|()| format!("Saved {path}")),
+                        );
+                    }
+                }
+                if ui.button("copy").clicked() {
+                    ui.ctx().copy_text(self.text.clone());
+                }
+            });
+        }
+        match &self.status {
+            Some(Ok(status)) => {
+                ui.label(status);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{e:#}"));
+            }
+            None => {}
+        }
+        loaded
+    }
+}
+
+/// The node table. Selecting a node loads its local pose; "local" and "world" load either pose.
+/// Returns the loaded pose and whether it is the world pose.
+fn nodes_ui(
+    ui: &mut egui::Ui,
+    gltf: &Gltf,
+    selected: &mut Option<usize>,
+) -> Option<(na::Isometry3<f64>, bool)> {
+    let mut loaded = None;
+    egui::Grid::new("gltf_nodes").striped(true).show(ui, |ui| {
+        for header in [
+            "#",
+            "name",
+            "parent",
+            "rotation [x, y, z, w]",
+            "translation",
+            "scale",
+            "matrix",
+            "",
+            "",
+        ] {
+            ui.label(egui::RichText::new(header).strong());
+        }
+        ui.end_row();
+        let pose = |matrix: &na::Matrix4<f64>| {
+            let (translation, rotation, _) = decompose(matrix);
+            na::Isometry3::from_parts(translation.into(), rotation)
+        };
+        for (index, node) in gltf.nodes.iter().enumerate() {
+            if ui
+                .selectable_label(*selected == Some(index), format!("{index}"))
+                .on_hover_text("select and load the pose in the parent's frame")
+                .clicked()
+            {
+                *selected = Some(index);
+                loaded = Some((pose(&node.local_matrix()), false));
+            }
+            ui.label(node.name.as_deref().unwrap_or_default());
+            ui.label(
+                gltf.parents
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .map_or_else(String::new, |parent| format!("{parent}")),
+            );
+            for values in [
+                node.rotation.as_ref().map(|v| &v[..]),
+                node.translation.as_ref().map(|v| &v[..]),
+                node.scale.as_ref().map(|v| &v[..]),
+            ] {
+                ui.label(values.map_or_else(|| "-".to_owned(), format_values));
+            }
+            ui.label(if node.matrix.is_some() { "yes" } else { "-" });
+            if ui
+                .small_button("local")
+                .on_hover_text("load the pose in the parent's frame")
+                .clicked()
+            {
+                *selected = Some(index);
+                loaded = Some((pose(&node.local_matrix()), false));
+            }
+            if let Some(world) = gltf.world_matrix(index)
+                && ui
+                    .small_button("world")
+                    .on_hover_text("load the pose in the scene's frame")
+                    .clicked()
+            {
+                *selected = Some(index);
+                loaded = Some((pose(&world), true));
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(index) = *selected
+        && let (Some(node), Some(world)) = (gltf.nodes.get(index), gltf.world_matrix(index))
+    {
+        let (_, local, _) = decompose(&node.local_matrix());
+        let (_, world, _) = decompose(&world);
+        for (name, quat) in [("local", local), ("world", world)] {
+            ui.label(format!(
+                "Node {index} {name} rotation [x, y, z, w]: [{}]",
+                format_values(quat.coords.as_slice())
+            ));
+        }
+    }
+    loaded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"{
+        "asset": {"version": "2.0"},
+        "nodes": [
+            {"name": "root", "children": [1], "translation": [0, 1, 0],
+             "rotation": [0, 0.7071067811865476, 0, 0.7071067811865476]},
+            {"name": "child", "matrix": [2,0,0,0, 0,2,0,0, 0,0,2,0, 1,0,0,1]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_world_transform() {
+        let gltf = parse(SCENE).expect("valid glTF");
+        let world = gltf.world_matrix(1).expect("acyclic");
+        let (translation, rotation, scale) = decompose(&world);
+        // A quarter turn about +Y takes the child's +X offset to -Z.
+        assert!(
+            (translation - na::Vector3::new(0.0, 1.0, -1.0)).norm() < 1e-12,
+            "child position in the scene"
+        );
+        assert!(
+            (rotation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12,
+            "inherited rotation"
+        );
+        assert!(
+            (scale - na::Vector3::repeat(2.0)).norm() < 1e-12,
+            "child scale"
+        );
+    }
+
+    #[test]
+    fn test_write_back() {
+        let mut gltf = parse(SCENE).expect("valid glTF");
+        let quat = na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), 0.5);
+        gltf.set_rotation(1, &quat).expect("node exists");
+        let reparsed = parse(&gltf.document.to_string()).expect("still valid");
+        let child = reparsed.nodes.get(1).expect("two nodes");
+        assert!(child.matrix.is_none(), "matrix replaced by TRS");
+        let (translation, rotation, scale) = decompose(&child.local_matrix());
+        assert!(rotation.angle_to(&quat) < 1e-12, "rotation written");
+        assert!(
+            (translation - na::Vector3::x()).norm() < 1e-12
+                && (scale - na::Vector3::repeat(2.0)).norm() < 1e-12,
+            "translation and scale kept"
+        );
+        gltf.set_world_rotation(1, &quat).expect("acyclic");
+        let (_, world, _) = decompose(&gltf.world_matrix(1).expect("acyclic"));
+        assert!(world.angle_to(&quat) < 1e-12, "world rotation written");
+    }
+}
@@ -0,0 +1,290 @@
+//! Interpolation between keyframe rotations.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+use crate::raw::RawStringType;
+
+/// Seconds the preview animation takes to go from the first to the last keyframe.
+const ANIMATION_DURATION: f64 = 3.0;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum InterpolationMethod {
+    Slerp,
+    Nlerp,
+    /// Spherical cubic through all keyframes, with Shoemake's control points.
+    Squad,
+}
+
+/// Rotation at `t` in `[0, 1]`, where the keyframes are evenly spaced along `t`.
+///
+/// Returns `None` when there are no keyframes.
+pub fn interpolate(
+    method: InterpolationMethod,
+    keyframes: &[na::UnitQuaternion<f64>],
+    t: f64,
+) -> Option<na::UnitQuaternion<f64>> {
+    let keyframes = same_hemisphere(keyframes);
+    let last = keyframes.len().checked_sub(1)?;
+    let position = t.clamp(0.0, 1.0) * last as f64;
+    let segment = (position.floor() as usize).min(last.saturating_sub(1));
+    let h = position - segment as f64;
+    let start = *keyframes.get(segment)?;
+    let Some(&end) = keyframes.get(segment + 1) else {
+        return Some(start);
+    };
+    Some(match method {
+        InterpolationMethod::Slerp => start.slerp(&end, h),
+        InterpolationMethod::Nlerp => start.nlerp(&end, h),
+        InterpolationMethod::Squad => {
+            let before = *keyframes.get(segment.wrapping_sub(1)).unwrap_or(&start);
+            let after = *keyframes.get(segment + 2).unwrap_or(&end);
+            let start_control = squad_control(&before, &start, &end);
+            let end_control = squad_control(&start, &end, &after);
+            start
+                .slerp(&end, h)
+                .slerp(&start_control.slerp(&end_control, h), 2.0 * h * (1.0 - h))
+        }
+    })
+}
+
+/// `count` rotations evenly spaced in `t` from the first to the last keyframe.
+pub fn samples(
+    method: InterpolationMethod,
+    keyframes: &[na::UnitQuaternion<f64>],
+    count: usize,
+) -> Vec<na::UnitQuaternion<f64>> {
+    let divisor = count.saturating_sub(1).max(1) as f64;
+    (0..count)
+        .filter_map(|i| interpolate(method, keyframes, i as f64 / divisor))
+        .collect()
+}
+
+/// Flips keyframes onto the hemisphere of their predecessor, so every segment takes the short way.
+fn same_hemisphere(keyframes: &[na::UnitQuaternion<f64>]) -> Vec<na::UnitQuaternion<f64>> {
+    let mut aligned = keyframes.to_vec();
+    make_continuous(&mut aligned);
+    aligned
+}
+
+/// Replaces each `q` with `-q` when its dot product with the (already corrected) previous
+/// sample is negative, and returns how many samples were flipped.
+pub fn make_continuous(quats: &mut [na::UnitQuaternion<f64>]) -> usize {
+    let mut flipped = 0;
+    let mut previous: Option<na::UnitQuaternion<f64>> = None;
+    for quat in quats {
+        if previous.is_some_and(|previous| previous.coords.dot(&quat.coords) < 0.0) {
+            *quat = na::UnitQuaternion::new_unchecked(-quat.into_inner());
+            flipped += 1;
+        }
+        previous = Some(*quat);
+    }
+    flipped
+}
+
+/// Shoemake's inner control point `qᵢ exp(-(log(qᵢ⁻¹qᵢ₊₁) + log(qᵢ⁻¹qᵢ₋₁)) / 4)`.
+fn squad_control(
+    before: &na::UnitQuaternion<f64>,
+    current: &na::UnitQuaternion<f64>,
+    after: &na::UnitQuaternion<f64>,
+) -> na::UnitQuaternion<f64> {
+    let inverse = current.inverse();
+    // `log(q)` is half the scaled axis, and `exp(v)` turns by twice `|v|`.
+    let tangent = ((inverse * after).scaled_axis() + (inverse * before).scaled_axis()) * -0.25;
+    current * na::UnitQuaternion::from_scaled_axis(tangent)
+}
+
+/// State of the interpolation panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct InterpolationTool {
+    keyframes: Vec<na::UnitQuaternion<f64>>,
+    method: InterpolationMethod,
+    t: f64,
+    #[serde(skip)]
+    playing: bool,
+    sample_count: usize,
+    export_type: RawStringType,
+    export: String,
+}
+
+impl Default for InterpolationTool {
+    fn default() -> Self {
+        Self {
+            keyframes: Vec::new(),
+            method: InterpolationMethod::Slerp,
+            t: 0.0,
+            playing: false,
+            sample_count: 10,
+            export_type: RawStringType::QuaternionWXYZ,
+            export: String::new(),
+        }
+    }
+}
+
+impl InterpolationTool {
+    /// Returns the interpolated rotation whenever `t` moves, to be shown in the main views.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        current: &na::UnitQuaternion<f64>,
+    ) -> Option<na::UnitQuaternion<f64>> {
+        self.keyframes_ui(ui, current);
+        let moved = self.playback_ui(ui);
+        self.export_ui(ui);
+        if moved {
+            interpolate(self.method, &self.keyframes, self.t)
+        } else {
+            None
+        }
+    }
+
+    fn keyframes_ui(&mut self, ui: &mut egui::Ui, current: &na::UnitQuaternion<f64>) {
+        ui.horizontal(|ui| {
+            if ui.button("set start").clicked() {
+                if self.keyframes.is_empty() {
+                    self.keyframes.push(*current);
+                } else if let Some(start) = self.keyframes.first_mut() {
+                    *start = *current;
+                }
+            }
+            if ui.button("set end").clicked() {
+                if self.keyframes.len() < 2 {
+                    self.keyframes.push(*current);
+                } else if let Some(end) = self.keyframes.last_mut() {
+                    *end = *current;
+                }
+            }
+            if ui.button("add keyframe").clicked() {
+                self.keyframes.push(*current);
+            }
+            if ui.button("clear").clicked() {
+                self.keyframes.clear();
+                self.playing = false;
+            }
+        });
+        ui.label("Keyframes are taken from the current rotation, entered in any view above.");
+        let mut removed = None;
+        for (i, keyframe) in self.keyframes.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "#{i}: [{:.4}, {:.4}, {:.4}, {:.4}]",
+                    keyframe.w, keyframe.i, keyframe.j, keyframe.k
+                ));
+                if ui.small_button("remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            self.keyframes.remove(i);
+        }
+    }
+
+    /// Returns whether `t` or the method changed.
+    fn playback_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut moved = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("method")
+                .selected_text(format!("{:?}", self.method))
+                .show_ui(ui, |ui| {
+                    for method in InterpolationMethod::iter() {
+                        moved |= ui
+                            .selectable_value(&mut self.method, method, format!("{method:?}"))
+                            .changed();
+                    }
+                });
+            if ui
+                .button(if self.playing { "pause" } else { "play" })
+                .clicked()
+            {
+                self.playing = !self.playing;
+            }
+        });
+        moved |= ui
+            .add(egui::Slider::new(&mut self.t, 0.0..=1.0).text("t"))
+            .changed();
+        if self.playing && self.keyframes.len() >= 2 {
+            let dt = f64::from(ui.input(|input| input.stable_dt));
+            self.t = (self.t + dt / ANIMATION_DURATION) % 1.0;
+            ui.ctx().request_repaint();
+            moved = true;
+        }
+        moved
+    }
+
+    fn export_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.sample_count)
+                    .range(2..=10000)
+                    .prefix("N = "),
+            );
+            egui::ComboBox::from_label("layout")
+                .selected_text(format!("{:?}", self.export_type))
+                .show_ui(ui, |ui| {
+                    for string_type in RawStringType::iter() {
+                        ui.selectable_value(
+                            &mut self.export_type,
+                            string_type,
+                            format!("{string_type:?}"),
+                        );
+                    }
+                });
+            if ui.button("export samples").clicked() {
+                self.export = samples(self.method, &self.keyframes, self.sample_count)
+                    .iter()
+                    .map(|sample| self.export_type.format(sample))
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+            }
+        });
+        if !self.export.is_empty() {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.export)
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(6),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_and_midpoint() {
+        let start = na::UnitQuaternion::identity();
+        let end = na::UnitQuaternion::from_axis_angle(&na::Vector3::z_axis(), 2.0);
+        for method in InterpolationMethod::iter() {
+            let keyframes = [start, end];
+            let at = |t| interpolate(method, &keyframes, t).expect("two keyframes");
+            assert!(
+                at(0.0).angle_to(&start) < 1e-12,
+                "{method:?} starts at start"
+            );
+            assert!(at(1.0).angle_to(&end) < 1e-12, "{method:?} ends at end");
+            assert!(
+                (at(0.5).angle() - 1.0).abs() < 1e-12,
+                "{method:?} is symmetric about the midpoint"
+            );
+        }
+    }
+
+    #[test]
+    fn test_squad_passes_through_keyframes() {
+        let keyframes = [
+            na::UnitQuaternion::identity(),
+            na::UnitQuaternion::from_euler_angles(0.5, 0.2, 0.0),
+            na::UnitQuaternion::from_euler_angles(0.1, 1.0, -0.4),
+            na::UnitQuaternion::from_euler_angles(-0.3, 0.4, 1.2),
+        ];
+        let sampled = samples(InterpolationMethod::Squad, &keyframes, 4);
+        for (sample, keyframe) in sampled.iter().zip(&keyframes) {
+            assert!(sample.angle_to(keyframe) < 1e-9, "squad interpolates");
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Rotational kinematics at an operating point: angular velocity, Euler rates and `q̇`.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+use crate::euler::{EulerFrame, EulerSequence};
+
+/// Distance from gimbal lock in degrees below which the Euler rates are flagged.
+const SINGULAR_WARNING_DEGREES: f64 = 10.0;
+/// `|det J|` below which the Euler-rate Jacobian is treated as singular.
+const SINGULAR_DETERMINANT: f64 = 1e-9;
+
+/// What the kinematics panel's numbers are.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum RateInput {
+    BodyOmega,
+    WorldOmega,
+    EulerRates,
+    QuaternionDerivative,
+}
+
+impl RateInput {
+    fn number_count(self) -> usize {
+        match self {
+            Self::QuaternionDerivative => 4,
+            Self::BodyOmega | Self::WorldOmega | Self::EulerRates => 3,
+        }
+    }
+}
+
+/// `J` with `ω_world = J · [ȧ₁, ȧ₂, ȧ₃]` at the given Euler angles (radians, sequence order).
+///
+/// Each column is the world-frame direction of one rotation axis at this attitude.
+pub fn euler_rate_jacobian(
+    sequence: EulerSequence,
+    frame: EulerFrame,
+    angles: [f64; 3],
+) -> na::Matrix3<f64> {
+    let [a1, a2, a3] = sequence.axes().map(|axis| na::Vector3::ith(axis, 1.0));
+    let [q1, q2, q3] = [(a1, angles[0]), (a2, angles[1]), (a3, angles[2])]
+        .map(|(axis, angle)| na::UnitQuaternion::from_scaled_axis(axis * angle));
+    let columns = match frame {
+        EulerFrame::Intrinsic => [a1, q1 * a2, q1 * q2 * a3],
+        EulerFrame::Extrinsic => [q3 * q2 * a1, q3 * a2, a3],
+    };
+    na::Matrix3::from_columns(&columns)
+}
+
+/// `q̇ = ½ q ⊗ (0, ω_body)`.
+pub fn quat_derivative(
+    quat: &na::UnitQuaternion<f64>,
+    body_omega: &na::Vector3<f64>,
+) -> na::Quaternion<f64> {
+    quat.quaternion() * na::Quaternion::from_imag(*body_omega) * 0.5
+}
+
+/// Body angular velocity `2 Im(q* ⊗ q̇)`, and the part of `q̇` along `q`, which a unit
+/// quaternion's derivative cannot have.
+pub fn body_omega_from_derivative(
+    quat: &na::UnitQuaternion<f64>,
+    derivative: &na::Quaternion<f64>,
+) -> (na::Vector3<f64>, f64) {
+    let product = quat.quaternion().conjugate() * derivative;
+    (product.imag() * 2.0, product.w)
+}
+
+/// The four equivalent descriptions of one angular velocity.
+struct Rates {
+    body: na::Vector3<f64>,
+    world: na::Vector3<f64>,
+    /// `None` at gimbal lock.
+    euler: Option<na::Vector3<f64>>,
+    derivative: na::Quaternion<f64>,
+}
+
+/// State of the kinematics panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct KinematicsTool {
+    input_kind: RateInput,
+    input: String,
+}
+
+impl Default for KinematicsTool {
+    fn default() -> Self {
+        Self {
+            input_kind: RateInput::BodyOmega,
+            input: "0.0, 0.0, 1.0".to_owned(),
+        }
+    }
+}
+
+impl KinematicsTool {
+    /// Converts the typed-in rates at `rotation`, with Euler angles of the main view's sequence.
+    /// Angular rates are in degrees per second when `degrees` is set.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        rotation: &na::UnitQuaternion<f64>,
+        sequence: EulerSequence,
+        frame: EulerFrame,
+        degrees: bool,
+    ) {
+        let unit = if degrees { "deg/s" } else { "rad/s" };
+        ui.label(format!(
+            "Operating point: the current rotation, {frame:?} {sequence:?} Euler angles"
+        ));
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("input")
+                .selected_text(format!("{:?}", self.input_kind))
+                .show_ui(ui, |ui| {
+                    for kind in RateInput::iter() {
+                        ui.selectable_value(&mut self.input_kind, kind, format!("{kind:?}"));
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut self.input).desired_width(200.0));
+        });
+        ui.label(match self.input_kind {
+            RateInput::QuaternionDerivative => "[ẇ, ẋ, ẏ, ż] in 1/s".to_owned(),
+            _ => format!("three rates in {unit}"),
+        });
+
+        let solution = crate::euler::from_quat(sequence, frame, rotation);
+        let jacobian = euler_rate_jacobian(sequence, frame, solution.angles);
+        let distance = solution.lock_distance.to_degrees();
+        if jacobian.determinant().abs() < SINGULAR_DETERMINANT {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "Gimbal lock: Euler rates are undefined, the first and third axes coincide",
+            );
+        } else if distance < SINGULAR_WARNING_DEGREES {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("{distance:.3}° from gimbal lock: Euler rates blow up as 1/|det J|"),
+            );
+        }
+
+        match self.rates(rotation, &jacobian, degrees) {
+            Ok((rates, radial)) => {
+                if radial.abs() > 1e-9 {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("q̇ has {radial:.4} along q, which is dropped"),
+                    );
+                }
+                rates_ui(ui, &rates, degrees, unit);
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+        }
+    }
+
+    /// All descriptions of the typed-in rates, and the dropped radial part of a typed-in `q̇`.
+    fn rates(
+        &self,
+        rotation: &na::UnitQuaternion<f64>,
+        jacobian: &na::Matrix3<f64>,
+        degrees: bool,
+    ) -> anyhow::Result<(Rates, f64)> {
+        let nums = crate::split_numbers(&self.input);
+        let wrong_count = || {
+            anyhow::anyhow!(
+                "{:?} takes {} numbers, got {}",
+                self.input_kind,
+                self.input_kind.number_count(),
+                nums.len()
+            )
+        };
+        let scale = if degrees { 1f64.to_radians() } else { 1.0 };
+        let vector = || match *nums.as_slice() {
+            [x, y, z] => Ok(na::Vector3::new(x, y, z) * scale),
+            _ => Err(wrong_count()),
+        };
+        let mut radial = 0.0;
+        let body = match self.input_kind {
+            RateInput::BodyOmega => vector()?,
+            RateInput::WorldOmega => rotation.inverse() * vector()?,
+            RateInput::EulerRates => rotation.inverse() * (jacobian * vector()?),
+            RateInput::QuaternionDerivative => {
+                let &[w, x, y, z] = nums.as_slice() else {
+                    return Err(wrong_count());
+                };
+                let (body, along) =
+                    body_omega_from_derivative(rotation, &na::Quaternion::new(w, x, y, z));
+                radial = along;
+                body
+            }
+        };
+        let world = rotation * body;
+        let euler = jacobian
+            .try_inverse()
+            .filter(|_| jacobian.determinant().abs() >= SINGULAR_DETERMINANT)
+            .map(|inverse| inverse * world);
+        Ok((
+            Rates {
+                body,
+                world,
+                euler,
+                derivative: quat_derivative(rotation, &body),
+            },
+            radial,
+        ))
+    }
+}
+
+fn rates_ui(ui: &mut egui::Ui, rates: &Rates, degrees: bool, unit: &str) {
+    let scale = if degrees { 1f64.to_degrees() } else { 1.0 };
+    let vector = |v: &na::Vector3<f64>| {
+        let v = v * scale;
+        format!("[{:.4}, {:.4}, {:.4}] {unit}", v.x, v.y, v.z)
+    };
+    egui::Grid::new("kinematics_rates").show(ui, |ui| {
+        ui.label("body ω");
+        ui.label(vector(&rates.body));
+        ui.end_row();
+        ui.label("world ω");
+        ui.label(vector(&rates.world));
+        ui.end_row();
+        ui.label("Euler rates");
+        ui.label(
+            rates
+                .euler
+                .as_ref()
+                .map_or_else(|| "undefined".to_owned(), vector),
+        );
+        ui.end_row();
+        let q = &rates.derivative;
+        ui.label("q̇");
+        ui.label(format!(
+            "[{:.4}, {:.4}, {:.4}, {:.4}] 1/s",
+            q.w, q.i, q.j, q.k
+        ));
+        ui.end_row();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_euler_rate_jacobian() {
+        let angles = [0.3, -0.7, 1.9];
+        let rates = na::Vector3::new(0.5, -1.0, 2.0);
+        let step = 1e-6;
+        for sequence in [EulerSequence::ZYX, EulerSequence::ZXZ] {
+            for frame in EulerFrame::iter() {
+                let before = crate::euler::to_quat(sequence, frame, angles);
+                let mut moved = angles;
+                for (angle, rate) in moved.iter_mut().zip(rates.iter()) {
+                    *angle += rate * step;
+                }
+                let after = crate::euler::to_quat(sequence, frame, moved);
+                // ω_world = log(q(t + h) q(t)⁻¹) / h
+                let numeric = (after * before.inverse()).scaled_axis() / step;
+                let analytic = euler_rate_jacobian(sequence, frame, angles) * rates;
+                assert!(
+                    (numeric - analytic).norm() < 1e-5,
+                    "{frame:?} {sequence:?} world angular velocity"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_quat_derivative_roundtrip() {
+        let quat = na::UnitQuaternion::from_euler_angles(0.4, 1.1, -2.0);
+        let omega = na::Vector3::new(0.2, -0.3, 1.5);
+        let derivative = quat_derivative(&quat, &omega);
+        let (recovered, radial) = body_omega_from_derivative(&quat, &derivative);
+        assert!((recovered - omega).norm() < 1e-12, "body angular velocity");
+        assert!(radial.abs() < 1e-12, "q̇ is tangent to the unit sphere");
+        // q̇ = ½ (0, ω_world) ⊗ q as well.
+        let world = na::Quaternion::from_imag(quat * omega) * quat.quaternion() * 0.5;
+        assert!((world - derivative).norm() < 1e-12, "world form");
+    }
+}
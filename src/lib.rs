@@ -1,19 +1,50 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+mod align;
 mod app;
+mod average;
+#[cfg(feature = "capi")]
+mod capi;
+mod cli;
+mod construct;
+mod convert;
+mod dh;
+mod dual;
+mod euler;
+mod exact;
+mod expr;
+mod gltf;
+mod interp;
+mod kinematics;
+mod plot;
+mod preview;
+#[cfg(all(feature = "python", not(target_arch = "wasm32")))]
+mod python;
+mod raw;
+#[cfg(any(test, feature = "python", feature = "capi", target_arch = "wasm32"))]
+mod repr;
+mod ros;
+mod structured;
+mod trajectory;
+mod triad;
+mod urdf;
+#[cfg(target_arch = "wasm32")]
+mod web;
 pub use app::TemplateApp;
+pub use cli::run_cli;
+/// A decimal number, optionally in scientific notation as printed by `NumPy`.
+const NUMBER_PATTERN: &str = r"-?\d+\.?\d*(?:[eE][-+]?\d+)?";
+
 fn render_numbers(text: &str) -> egui::text::LayoutJob {
     let mut layout_job: egui::text::LayoutJob = Default::default();
-    let re = regex::Regex::new(r"-?\d+\.?\d*").expect("Failed to compile regex");
+    let re = regex::Regex::new(NUMBER_PATTERN).expect("Failed to compile regex");
     let mut rendered = 0;
     for match_e in re.find_iter(text) {
         layout_job.append(&text[rendered..match_e.start()], 0.0, egui::TextFormat {
             background: egui::Color32::GRAY,
             ..Default::default()
         });
-        layout_job.append(&text[match_e.start()..match_e.end()], 0.0, egui::TextFormat {
-            ..Default::default()
-        });
+        layout_job.append(&text[match_e.start()..match_e.end()], 0.0, egui::TextFormat::default());
         rendered = match_e.end();
     };
     layout_job.append(&text[rendered..], 0.0, egui::TextFormat {
@@ -21,14 +52,68 @@ fn render_numbers(text: &str) -> egui::text::LayoutJob {
         ..Default::default()
     });
     rendered = text.len();
-    debug_assert_eq!(rendered, text.len());
+    debug_assert_eq!(rendered, text.len(), "the whole text must be laid out");
     layout_job
 }
 
 fn split_numbers(s: &str) -> Vec<f64> {
-    let re = regex::Regex::new(r"-?\d+\.?\d*").expect("Failed to compile regex");
+    let re = regex::Regex::new(NUMBER_PATTERN).expect("Failed to compile regex");
     re.captures_iter(s).map(|c| c[0].parse().unwrap_or_default()).collect::<Vec<f64>>()
 }
+fn parse_vector3(fields: &[(String, String); 3]) -> anyhow::Result<nalgebra::Vector3<f64>> {
+    let vector = nalgebra::Vector3::new(
+        expr::eval(&fields[0].1)?,
+        expr::eval(&fields[1].1)?,
+        expr::eval(&fields[2].1)?,
+    );
+    if !vector.iter().all(|x| x.is_finite()) {
+        anyhow::bail!("values must be finite");
+    }
+    Ok(vector)
+}
+
+/// Fields for [`vector3_input`] labelled X, Y and Z.
+fn vector3_fields(values: [f64; 3]) -> [(String, String); 3] {
+    let [x, y, z] = values;
+    [
+        ("X".to_owned(), format!("{x:.4}")),
+        ("Y".to_owned(), format!("{y:.4}")),
+        ("Z".to_owned(), format!("{z:.4}")),
+    ]
+}
+
+/// A row of three labelled fields for a vector parsed with [`parse_vector3`].
+fn vector3_input(ui: &mut egui::Ui, fields: &mut [(String, String); 3]) {
+    ui.horizontal(|ui| {
+        for field in fields {
+            ui.label(&field.0);
+            ui.add(egui::TextEdit::singleline(&mut field.1).desired_width(60.0));
+            expr::hint(ui, &field.1, false);
+        }
+    });
+}
+
+/// Contents of a text file with one of `extensions` dropped onto the window this frame.
+fn dropped_text(ctx: &egui::Context, extensions: &[&str]) -> Option<anyhow::Result<String>> {
+    let matches = |file: &&egui::DroppedFile| {
+        let name = file
+            .path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map_or(file.name.clone(), |name| name.to_string_lossy().into_owned())
+            .to_lowercase();
+        extensions.iter().any(|extension| name.ends_with(extension))
+    };
+    let file = ctx.input(|input| input.raw.dropped_files.iter().find(matches).cloned())?;
+    Some(if let Some(bytes) = file.bytes {
+        String::from_utf8(bytes.to_vec()).map_err(Into::into)
+    } else if let Some(path) = file.path {
+        std::fs::read_to_string(path).map_err(Into::into)
+    } else {
+        Err(anyhow::anyhow!("the dropped file has no contents"))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,5 +121,6 @@ mod tests {
     #[test]
     fn test_regex() {
         assert_eq!(split_numbers("1312.3    413.423,,,,, 5234534 ,-2.0,  -0.2 fwefawe, 1234"), vec![1312.3, 413.423, 5234534.0, -2.0, -0.2, 1234.0]);
+        assert_eq!(split_numbers("[ 1.5e-03, -2E+2 ]"), vec![0.0015, -200.0]);
     }
 }
\ No newline at end of file
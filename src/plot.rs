@@ -0,0 +1,190 @@
+//! Painted line plots of rotation sequences.
+
+use std::f64::consts::{PI, TAU};
+
+use nalgebra as na;
+
+/// Room for the value labels left of and below the plot area.
+const MARGIN: egui::Vec2 = egui::vec2(60.0, 16.0);
+
+const COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(230, 60, 60),
+    egui::Color32::from_rgb(60, 200, 60),
+    egui::Color32::from_rgb(70, 110, 240),
+    egui::Color32::from_rgb(200, 160, 40),
+];
+
+/// One named curve, sampled at the plot's shared x values.
+pub struct Series {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+/// Adds multiples of 2π so consecutive angles (radians) never jump by more than π.
+pub fn unwrap_angles(angles: &[f64]) -> Vec<f64> {
+    let mut offset = 0.0;
+    let mut previous = None;
+    angles
+        .iter()
+        .map(|&angle| {
+            if let Some(previous) = previous {
+                let jump: f64 = angle + offset - previous;
+                offset -= TAU * ((jump + PI) / TAU).floor();
+            }
+            let unwrapped = angle + offset;
+            previous = Some(unwrapped);
+            unwrapped
+        })
+        .collect()
+}
+
+/// Rotation angle between consecutive samples divided by their spacing in `x`; one value fewer
+/// than `quats`.
+pub fn angular_rates(quats: &[na::UnitQuaternion<f64>], x: &[f64]) -> Vec<f64> {
+    quats
+        .windows(2)
+        .zip(x.windows(2))
+        .filter_map(|pair| match pair {
+            ([before, after], [start, end]) => Some(before.angle_to(after) / (end - start)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn finite_range(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values
+        .filter(|value| value.is_finite())
+        .fold(None, |range, value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((value.min(min), value.max(max))),
+        })
+}
+
+/// Draws `series` against `x` with a legend, value ranges, and a readout under the pointer.
+/// A series may be shorter than `x`; it is drawn against the leading x values.
+pub fn line_plot(ui: &mut egui::Ui, x: &[f64], series: &[Series], unit: &str, height: f32) {
+    ui.horizontal(|ui| {
+        for (curve, color) in series.iter().zip(COLORS.iter().cycle()) {
+            ui.colored_label(*color, &curve.name);
+        }
+        ui.label(format!("({unit})"));
+    });
+    let size = egui::vec2(ui.available_width(), height);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let outer = response.rect;
+    let rect = egui::Rect::from_min_max(
+        outer.min + egui::vec2(MARGIN.x, 0.0),
+        outer.max - egui::vec2(0.0, MARGIN.y),
+    );
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+    let (Some((x_min, x_max)), Some((y_min, y_max))) = (
+        finite_range(x.iter().copied()),
+        finite_range(series.iter().flat_map(|curve| curve.values.iter().copied())),
+    ) else {
+        return;
+    };
+    let x_span = (x_max - x_min).max(f64::EPSILON);
+    let y_pad = ((y_max - y_min) * 0.05).max(1e-9);
+    let (y_min, y_max) = (y_min - y_pad, y_max + y_pad);
+    let to_screen = |x: f64, y: f64| {
+        egui::pos2(
+            rect.left() + ((x - x_min) / x_span) as f32 * rect.width(),
+            rect.bottom() - ((y - y_min) / (y_max - y_min)) as f32 * rect.height(),
+        )
+    };
+
+    let text_color = ui.visuals().text_color();
+    let font = egui::FontId::monospace(11.0);
+    if y_min < 0.0 && y_max > 0.0 {
+        let zero = to_screen(x_min, 0.0).y;
+        painter.hline(
+            rect.x_range(),
+            zero,
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+    }
+    for (y, align) in [
+        (y_max, egui::Align2::RIGHT_TOP),
+        (y_min, egui::Align2::RIGHT_BOTTOM),
+    ] {
+        let pos = egui::pos2(rect.left() - 4.0, to_screen(x_min, y).y);
+        painter.text(pos, align, format!("{y:.3}"), font.clone(), text_color);
+    }
+    for (x, align) in [
+        (x_min, egui::Align2::LEFT_TOP),
+        (x_max, egui::Align2::RIGHT_TOP),
+    ] {
+        let pos = egui::pos2(to_screen(x, y_min).x, rect.bottom() + 2.0);
+        painter.text(pos, align, format!("{x:.3}"), font.clone(), text_color);
+    }
+
+    // More than about two points per pixel only costs time.
+    let step = (x.len() / (2 * rect.width().max(1.0) as usize).max(1)).max(1);
+    for (curve, color) in series.iter().zip(COLORS.iter().cycle()) {
+        let points = x
+            .iter()
+            .zip(&curve.values)
+            .step_by(step)
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|(&x, &y)| to_screen(x, y))
+            .collect::<Vec<_>>();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, *color)));
+    }
+
+    let Some(pointer) = response.hover_pos().filter(|pos| rect.contains(*pos)) else {
+        return;
+    };
+    let pointer_x = x_min + f64::from((pointer.x - rect.left()) / rect.width()) * x_span;
+    let Some((index, &sample_x)) = x
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - pointer_x).abs().total_cmp(&(*b - pointer_x).abs()))
+    else {
+        return;
+    };
+    painter.vline(
+        to_screen(sample_x, y_min).x,
+        rect.y_range(),
+        egui::Stroke::new(1.0, text_color),
+    );
+    let readout = series
+        .iter()
+        .filter_map(|curve| Some(format!("{} {:.4}", curve.name, curve.values.get(index)?)))
+        .collect::<Vec<_>>()
+        .join("  ");
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 4.0),
+        egui::Align2::LEFT_TOP,
+        format!("[{index}] x {sample_x:.4}  {readout}"),
+        font,
+        text_color,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwrap_angles() {
+        // A steady turn through ±π, wrapped into (-π, π].
+        let wrapped = [0.0, 2.0, -2.2, -0.5, 0.3];
+        let unwrapped = unwrap_angles(&wrapped);
+        for (u, w) in unwrapped.iter().zip(wrapped) {
+            let turns = (u - w) / TAU;
+            assert!(
+                (turns - turns.round()).abs() < 1e-12,
+                "same angle modulo 2π"
+            );
+        }
+        assert!(
+            unwrapped.windows(2).all(|pair| pair.first() < pair.last()),
+            "no jumps"
+        );
+        assert!(
+            (unwrapped.last().copied().unwrap_or_default() - (0.3 + TAU)).abs() < 1e-12,
+            "a full turn accumulates"
+        );
+    }
+}
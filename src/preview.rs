@@ -0,0 +1,48 @@
+//! A small painted 3D view of the rotated coordinate frame.
+
+use nalgebra as na;
+
+/// Direction from the origin towards the viewer, in world coordinates.
+const EYE: [f64; 3] = [1.0, 0.7, 0.6];
+
+/// Draws the world axes (thin) and the rotated axes (thick), with world `z` pointing up.
+pub fn axes_preview(ui: &mut egui::Ui, rotation: &na::UnitQuaternion<f64>, size: f32) {
+    let (response, painter) = ui.allocate_painter(egui::Vec2::splat(size), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+    let eye = na::Vector3::from(EYE).normalize();
+    let right = na::Vector3::z().cross(&eye).normalize();
+    let up = eye.cross(&right);
+    let scale = f64::from(size) * 0.4;
+    let project = |v: &na::Vector3<f64>| {
+        rect.center() + egui::vec2((v.dot(&right) * scale) as f32, (-v.dot(&up) * scale) as f32)
+    };
+
+    let colors = [
+        egui::Color32::from_rgb(230, 60, 60),
+        egui::Color32::from_rgb(60, 200, 60),
+        egui::Color32::from_rgb(70, 110, 240),
+    ];
+    let mut axes = Vec::with_capacity(6);
+    for (i, (color, name)) in colors.iter().zip(["x", "y", "z"]).enumerate() {
+        let world = na::Vector3::ith(i, 1.0);
+        axes.push((world, color.gamma_multiply(0.35), 1.0, None));
+        axes.push((rotation * world, *color, 3.0, Some(name)));
+    }
+    // Paint far axes first so the ones pointing at the viewer stay on top.
+    axes.sort_by(|a, b| a.0.dot(&eye).total_cmp(&b.0.dot(&eye)));
+    for (axis, color, width, name) in axes {
+        let tip = project(&axis);
+        painter.line_segment([rect.center(), tip], egui::Stroke::new(width, color));
+        if let Some(name) = name {
+            painter.text(
+                tip,
+                egui::Align2::CENTER_CENTER,
+                name,
+                egui::FontId::proportional(14.0),
+                color,
+            );
+        }
+    }
+}
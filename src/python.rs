@@ -0,0 +1,160 @@
+//! The `rottool` Python extension module, built with `maturin build --features python`, which
+//! builds the library as a `cdylib` itself.
+//!
+//! ```python
+//! import numpy as np, rottool
+//! rottool.convert(np.array([[0.1, 0.2, 0.3]]), "RotationVector", "QuaternionWXYZ")
+//! rottool.convert(angles, "Euler", "RotationMatrix", sequence="ZYX", frame="Intrinsic")
+//! quat, translation = rottool.parse(tf2_echo_text, "Tf2Echo")
+//! rottool.format(quats_wxyz, "RowMajor4x4", translations)
+//! ```
+
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray as _, PyArray1, PyArray2, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use strum::IntoEnumIterator as _;
+
+use crate::euler::{EulerFrame, EulerSequence};
+use crate::raw::RawStringType;
+use crate::repr::{Representation, by_name};
+
+/// A quaternion `[w, x, y, z]` and an optional translation.
+type Pose<'py> = (Bound<'py, PyArray1<f64>>, Option<Bound<'py, PyArray1<f64>>>);
+
+fn value_error(e: &anyhow::Error) -> PyErr {
+    PyValueError::new_err(format!("{e:#}"))
+}
+
+fn euler_settings(sequence: &str, frame: &str) -> PyResult<(EulerSequence, EulerFrame)> {
+    Ok((
+        by_name(sequence).map_err(|e| value_error(&e))?,
+        by_name(frame).map_err(|e| value_error(&e))?,
+    ))
+}
+
+fn rows_to_array(rows: &[Vec<f64>], columns: usize) -> PyResult<Array2<f64>> {
+    Array2::from_shape_vec((rows.len(), columns), rows.concat())
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Converts each row of `values` from one representation to another. Names are those of
+/// `representations()`; Euler angles are in radians in `sequence` and `frame`.
+#[pyfunction]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "pyo3 extracts arguments by value"
+)]
+#[pyo3(signature = (values, from_repr, to_repr, sequence = "ZYX", frame = "Intrinsic"))]
+fn convert<'py>(
+    py: Python<'py>,
+    values: PyReadonlyArray2<'py, f64>,
+    from_repr: &str,
+    to_repr: &str,
+    sequence: &str,
+    frame: &str,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let from = by_name::<Representation>(from_repr).map_err(|e| value_error(&e))?;
+    let to = by_name::<Representation>(to_repr).map_err(|e| value_error(&e))?;
+    let (sequence, frame) = euler_settings(sequence, frame)?;
+    let rows = values
+        .as_array()
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let quat = from
+                .quat(&row.to_vec(), sequence, frame)
+                .map_err(|e| value_error(&e.context(format!("row {index}"))))?;
+            Ok(to.values(&quat, sequence, frame))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(rows_to_array(&rows, to.number_count())?.into_pyarray(py))
+}
+
+/// Reads one rotation from raw text in a layout of `layouts()`. Returns the quaternion as
+/// `[w, x, y, z]` and the translation, or `None` for layouts without one.
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, text: &str, layout: &str) -> PyResult<Pose<'py>> {
+    let layout = by_name::<RawStringType>(layout).map_err(|e| value_error(&e))?;
+    let (quat, translation) = crate::repr::parse_raw(text, layout).map_err(|e| value_error(&e))?;
+    Ok((
+        vec![quat.w, quat.i, quat.j, quat.k].into_pyarray(py),
+        translation.map(|t| t.as_slice().to_vec().into_pyarray(py)),
+    ))
+}
+
+/// Formats each `[w, x, y, z]` row of `quats` as raw text in a layout of `layouts()`, with the
+/// matching row of `translations` for layouts that carry one.
+#[pyfunction]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "pyo3 extracts arguments by value"
+)]
+#[pyo3(signature = (quats, layout, translations = None))]
+fn format(
+    quats: PyReadonlyArray2<'_, f64>,
+    layout: &str,
+    translations: Option<PyReadonlyArray2<'_, f64>>,
+) -> PyResult<Vec<String>> {
+    let layout = by_name::<RawStringType>(layout).map_err(|e| value_error(&e))?;
+    let quats = quats.as_array();
+    let translations = translations.as_ref().map(PyReadonlyArray2::as_array);
+    if let Some(translations) = &translations
+        && translations.nrows() != quats.nrows()
+    {
+        return Err(PyValueError::new_err(format!(
+            "{} quaternions but {} translations",
+            quats.nrows(),
+            translations.nrows()
+        )));
+    }
+    quats
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let quat = Representation::QuaternionWXYZ
+                .quat(&row.to_vec(), EulerSequence::ZYX, EulerFrame::Intrinsic)
+                .map_err(|e| value_error(&e.context(format!("row {index}"))))?;
+            let translation = match &translations {
+                Some(translations) => match *translations.row(index).to_vec().as_slice() {
+                    [x, y, z] => nalgebra::Vector3::new(x, y, z),
+                    _ => {
+                        return Err(PyValueError::new_err(format!(
+                            "translation row {index} must have 3 numbers"
+                        )));
+                    }
+                },
+                None => nalgebra::Vector3::zeros(),
+            };
+            Ok(layout.format_transform(&quat, &translation))
+        })
+        .collect()
+}
+
+/// Names accepted by `convert`.
+#[pyfunction]
+fn representations() -> Vec<String> {
+    Representation::iter()
+        .map(|repr| format!("{repr:?}"))
+        .collect()
+}
+
+/// Names accepted by `parse` and `format`.
+#[pyfunction]
+fn layouts() -> Vec<String> {
+    RawStringType::iter()
+        .map(|layout| format!("{layout:?}"))
+        .collect()
+}
+
+#[pymodule]
+fn rottool(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(convert, module)?)?;
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add_function(wrap_pyfunction!(format, module)?)?;
+    module.add_function(wrap_pyfunction!(representations, module)?)?;
+    module.add_function(wrap_pyfunction!(layouts, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,450 @@
+//! Plain-text number layouts accepted by the raw string import and export.
+
+use std::f64::consts::PI;
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+/// Fit error at which a layout's weight in [`detect`] drops by a factor of e.
+const DETECT_TOLERANCE: f64 = 1e-3;
+/// Share of the best candidate's confidence above which an alternative is worth a warning.
+pub const AMBIGUOUS_CONFIDENCE: f64 = 0.25;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum RawStringType {
+    ColumnMajor4x4,
+    RowMajor4x4,
+    ColumnMajor3x3,
+    RowMajor3x3,
+    QuaternionWXYZ,
+    QuaternionXYZW,
+    /// First two columns of the rotation matrix, `r11 r21 r31 r12 r22 r32`.
+    Continuous6D,
+    /// Unconstrained row-major 3x3 matrix, projected onto the closest rotation by SVD.
+    Svd9D,
+    /// Unit dual quaternion, real `w x y z` then dual `w x y z`.
+    DualQuaternionWXYZ,
+    /// Unit dual quaternion, real `x y z w` then dual `x y z w`.
+    DualQuaternionXYZW,
+    /// `ros2 run tf2_ros tf2_echo` output; only the translation and quaternion lines are read.
+    Tf2Echo,
+    /// `geometry_msgs` `Transform`, `Pose` or `Quaternion` YAML from `ros2 topic echo`.
+    RosYaml,
+    /// Axis times angle in radians.
+    RotationVector,
+    /// Extrinsic `XYZ` roll, pitch and yaw in radians, as in ROS and URDF.
+    RollPitchYaw,
+}
+
+/// A layout [`detect`] considers plausible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub layout: RawStringType,
+    /// Share of the total weight of all candidates, in `(0, 1]`.
+    pub confidence: f64,
+}
+
+impl RawStringType {
+    /// How many numbers one rotation takes in this layout.
+    pub fn number_count(self) -> usize {
+        match self {
+            Self::ColumnMajor4x4 | Self::RowMajor4x4 => 16,
+            Self::ColumnMajor3x3 | Self::RowMajor3x3 | Self::Svd9D => 9,
+            Self::QuaternionWXYZ | Self::QuaternionXYZW => 4,
+            Self::Continuous6D => 6,
+            Self::DualQuaternionWXYZ | Self::DualQuaternionXYZW => 8,
+            Self::Tf2Echo | Self::RosYaml => 7,
+            Self::RotationVector | Self::RollPitchYaw => 3,
+        }
+    }
+
+    /// The numbers of one rotation in `text`. The ROS layouts pick them out by key, as
+    /// `tx ty tz qx qy qz qw` or just `qx qy qz qw`; the others take every number in order.
+    ///
+    /// # Errors
+    /// When a ROS message is incomplete.
+    pub fn numbers(self, text: &str) -> anyhow::Result<Vec<f64>> {
+        Ok(match self {
+            Self::Tf2Echo => crate::ros::parse_tf2_echo(text)?,
+            Self::RosYaml => crate::ros::parse_yaml(text)?,
+            _ => crate::split_numbers(text),
+        })
+    }
+
+    pub fn parse(self, nums: &[f64]) -> anyhow::Result<na::UnitQuaternion<f64>> {
+        Ok(match self {
+            Self::ColumnMajor4x4 => {
+                if nums.len() == 16 {
+                    let transform_mat = na::Matrix4::from_column_slice(nums);
+                    let mut rot = na::Matrix3::identity();
+                    rot.copy_from(&transform_mat.fixed_view::<3, 3>(0, 0));
+                    na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(&rot))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::RowMajor4x4 => {
+                if nums.len() == 16 {
+                    let transform_mat = na::Matrix4::from_row_slice(nums);
+                    let mut rot = na::Matrix3::identity();
+                    rot.copy_from(&transform_mat.fixed_view::<3, 3>(0, 0));
+                    na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(&rot))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::ColumnMajor3x3 => {
+                if nums.len() == 9 {
+                    na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
+                        &na::Matrix3::from_column_slice(nums),
+                    ))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::RowMajor3x3 => {
+                if nums.len() == 9 {
+                    na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(
+                        &na::Matrix3::from_row_slice(nums),
+                    ))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::QuaternionWXYZ => {
+                if let &[w, x, y, z] = nums {
+                    na::UnitQuaternion::from_quaternion(na::Quaternion::new(w, x, y, z))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::QuaternionXYZW => {
+                if nums.len() == 4 {
+                    na::UnitQuaternion::from_quaternion(na::Quaternion::from_vector(
+                        na::Vector4::from_column_slice(nums),
+                    ))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::Continuous6D => {
+                if let &[a, b, c, d, e, f] = nums {
+                    na::UnitQuaternion::from_rotation_matrix(&crate::convert::rotation_from_6d(
+                        &na::Vector3::new(a, b, c),
+                        &na::Vector3::new(d, e, f),
+                    )?)
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::Svd9D => {
+                if nums.len() == 9 {
+                    na::UnitQuaternion::from_rotation_matrix(&crate::convert::rotation_from_svd(
+                        &na::Matrix3::from_row_slice(nums),
+                    ))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::DualQuaternionWXYZ | Self::DualQuaternionXYZW => self.dual_pose(nums)?.rotation,
+            Self::RotationVector => {
+                if let &[x, y, z] = nums {
+                    na::UnitQuaternion::from_scaled_axis(na::Vector3::new(x, y, z))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::RollPitchYaw => {
+                if let &[roll, pitch, yaw] = nums {
+                    crate::urdf::rpy_to_quat([roll, pitch, yaw])
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+            Self::Tf2Echo | Self::RosYaml => {
+                if let &[.., x, y, z, w] = nums
+                    && (nums.len() == 4 || nums.len() == 7)
+                {
+                    na::UnitQuaternion::from_quaternion(na::Quaternion::new(w, x, y, z))
+                } else {
+                    anyhow::bail!("len wrong");
+                }
+            }
+        })
+    }
+
+    /// The pose of the dual quaternion layouts.
+    fn dual_pose(self, nums: &[f64]) -> anyhow::Result<na::Isometry3<f64>> {
+        match (self, nums) {
+            (Self::DualQuaternionWXYZ, &[rw, rx, ry, rz, dw, dx, dy, dz])
+            | (Self::DualQuaternionXYZW, &[rx, ry, rz, rw, dx, dy, dz, dw]) => {
+                crate::dual::to_isometry([rw, rx, ry, rz], [dw, dx, dy, dz])
+            }
+            _ => anyhow::bail!("len wrong"),
+        }
+    }
+
+    /// The translation column of the 4x4 layouts, or the translation of a dual quaternion.
+    pub fn translation(self, nums: &[f64]) -> Option<na::Vector3<f64>> {
+        let transform = match self {
+            Self::DualQuaternionWXYZ | Self::DualQuaternionXYZW => {
+                return self
+                    .dual_pose(nums)
+                    .ok()
+                    .map(|pose| pose.translation.vector);
+            }
+            Self::Tf2Echo | Self::RosYaml => {
+                return match nums {
+                    &[x, y, z, _, _, _, _] => Some(na::Vector3::new(x, y, z)),
+                    _ => None,
+                };
+            }
+            Self::ColumnMajor4x4 if nums.len() == 16 => na::Matrix4::from_column_slice(nums),
+            Self::RowMajor4x4 if nums.len() == 16 => na::Matrix4::from_row_slice(nums),
+            _ => return None,
+        };
+        Some(transform.fixed_view::<3, 1>(0, 3).into_owned())
+    }
+
+    /// The rotation part of matrix layouts, as typed in.
+    fn raw_matrix(self, nums: &[f64]) -> Option<na::Matrix3<f64>> {
+        match self {
+            Self::ColumnMajor4x4 if nums.len() == 16 => Some(
+                na::Matrix4::from_column_slice(nums)
+                    .fixed_view::<3, 3>(0, 0)
+                    .into_owned(),
+            ),
+            Self::RowMajor4x4 if nums.len() == 16 => Some(
+                na::Matrix4::from_row_slice(nums)
+                    .fixed_view::<3, 3>(0, 0)
+                    .into_owned(),
+            ),
+            Self::ColumnMajor3x3 if nums.len() == 9 => Some(na::Matrix3::from_column_slice(nums)),
+            Self::RowMajor3x3 | Self::Svd9D if nums.len() == 9 => {
+                Some(na::Matrix3::from_row_slice(nums))
+            }
+            _ => None,
+        }
+    }
+
+    /// Frobenius distance between the typed-in numbers and the rotation they were parsed into.
+    pub fn residual(self, nums: &[f64], quat: &na::UnitQuaternion<f64>) -> Option<f64> {
+        let rot = quat.to_rotation_matrix();
+        match self {
+            Self::QuaternionWXYZ | Self::QuaternionXYZW => {
+                Some((na::DVector::from_column_slice(nums).norm() - 1.0).abs())
+            }
+            Self::Tf2Echo | Self::RosYaml => {
+                let quat = nums.get(nums.len().checked_sub(4)?..)?;
+                Some((na::DVector::from_column_slice(quat).norm() - 1.0).abs())
+            }
+            Self::DualQuaternionWXYZ | Self::DualQuaternionXYZW => {
+                let pose = self.dual_pose(nums).ok()?;
+                // Either sign of the dual quaternion is the same pose.
+                let typed = na::DVector::from_column_slice(nums);
+                let expected = na::DVector::from_column_slice(&self.dual_numbers(&pose));
+                Some((&typed - &expected).norm().min((typed + expected).norm()))
+            }
+            Self::Continuous6D if nums.len() == 6 => Some(
+                (na::Matrix3x2::from_column_slice(nums) - rot.matrix().fixed_columns::<2>(0))
+                    .norm(),
+            ),
+            _ => self
+                .raw_matrix(nums)
+                .map(|matrix| (matrix - rot.matrix()).norm()),
+        }
+    }
+
+    pub fn format(self, quat: &na::UnitQuaternion<f64>) -> String {
+        self.format_transform(quat, &na::Vector3::zeros())
+    }
+
+    /// Like [`Self::format`], filling in `translation` for the 4x4 layouts.
+    pub fn format_transform(
+        self,
+        quat: &na::UnitQuaternion<f64>,
+        translation: &na::Vector3<f64>,
+    ) -> String {
+        let rot = quat.to_rotation_matrix();
+        let mut transform = na::Matrix4::identity();
+        transform
+            .fixed_view_mut::<3, 3>(0, 0)
+            .copy_from(rot.matrix());
+        transform
+            .fixed_view_mut::<3, 1>(0, 3)
+            .copy_from(translation);
+        match self {
+            Self::ColumnMajor4x4 => format_numbers(transform.as_slice(), 4),
+            Self::RowMajor4x4 => format_numbers(transform.transpose().as_slice(), 4),
+            Self::ColumnMajor3x3 => format_numbers(rot.matrix().as_slice(), 3),
+            Self::RowMajor3x3 | Self::Svd9D => {
+                format_numbers(rot.matrix().transpose().as_slice(), 3)
+            }
+            Self::QuaternionWXYZ => format_numbers(&[quat.w, quat.i, quat.j, quat.k], 4),
+            Self::QuaternionXYZW => format_numbers(quat.coords.as_slice(), 4),
+            Self::Continuous6D => format_numbers(rot.matrix().fixed_columns::<2>(0).as_slice(), 3),
+            Self::DualQuaternionWXYZ | Self::DualQuaternionXYZW => format_numbers(
+                &self.dual_numbers(&na::Isometry3::from_parts((*translation).into(), *quat)),
+                4,
+            ),
+            Self::RotationVector => format_numbers(quat.scaled_axis().as_slice(), 3),
+            Self::RollPitchYaw => format_numbers(&crate::urdf::quat_to_rpy(quat), 3),
+            Self::Tf2Echo => crate::ros::format_tf2_echo(quat, translation),
+            Self::RosYaml => crate::ros::format_yaml(quat, translation),
+        }
+    }
+
+    /// How far `nums` are from a rotation in this layout: the residual, plus for 4x4 layouts the
+    /// distance of the row or column that should be `[0 0 0 1]`. `None` when they do not parse.
+    fn fit_error(self, nums: &[f64]) -> Option<f64> {
+        let quat = self.parse(nums).ok()?;
+        let residual = self.residual(nums, &quat).unwrap_or(0.0);
+        let bottom = match self {
+            Self::ColumnMajor4x4 => [3, 7, 11, 15],
+            Self::RowMajor4x4 => [12, 13, 14, 15],
+            // Any matrix projects, so SVD only wins when the plain matrices are far off.
+            Self::Svd9D => return Some(3.0 * DETECT_TOLERANCE),
+            _ => return Some(residual),
+        };
+        let bottom_error = bottom
+            .iter()
+            .zip([0.0, 0.0, 0.0, 1.0])
+            .map(|(&index, expected)| Some((nums.get(index)? - expected).powi(2)))
+            .sum::<Option<f64>>()?
+            .sqrt();
+        Some(residual + bottom_error)
+    }
+
+    /// How likely this layout is for `nums` before looking at the fit. Quaternions usually lead
+    /// with their largest component, the scalar part, near identity; long rotation vectors are
+    /// rarer than Euler angles past π.
+    fn prior(self, nums: &[f64]) -> f64 {
+        let magnitude = |index: usize| nums.get(index).map_or(0.0, |x: &f64| x.abs());
+        match self {
+            Self::QuaternionWXYZ | Self::DualQuaternionWXYZ => 1.0 + magnitude(0),
+            Self::QuaternionXYZW | Self::DualQuaternionXYZW => 1.0 + magnitude(3),
+            Self::RotationVector if na::DVector::from_column_slice(nums).norm() > PI => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    /// The eight numbers of `pose` in the dual quaternion layouts.
+    fn dual_numbers(self, pose: &na::Isometry3<f64>) -> [f64; 8] {
+        let parts: [[f64; 4]; 2] = crate::dual::wxyz(&crate::dual::from_isometry(pose)).into();
+        let [real, dual] = parts.map(|[w, x, y, z]| match self {
+            Self::DualQuaternionXYZW => [x, y, z, w],
+            _ => [w, x, y, z],
+        });
+        let [rw, rx, ry, rz] = real;
+        let [dw, dx, dy, dz] = dual;
+        [rw, rx, ry, rz, dw, dx, dy, dz]
+    }
+}
+
+/// Plausible layouts of `text`, most confident first; empty when none parses.
+///
+/// ROS output is recognized by its keys. Plain numbers are matched by count and scored by how
+/// close they are to a rotation in each layout, so a 4x4 matrix's major order follows its
+/// `[0 0 0 1]` row, while orders that fit equally well (3x3 transposes, quaternion orders) share
+/// the confidence and show up as alternatives.
+pub fn detect(text: &str) -> Vec<Detection> {
+    let structured = if text.contains("Quaternion") && text.contains('[') {
+        Some(RawStringType::Tf2Echo)
+    } else if text.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("w:") || line.contains("{x:") || line.contains(", w:")
+    }) {
+        Some(RawStringType::RosYaml)
+    } else {
+        None
+    };
+    if let Some(layout) = structured
+        && layout.numbers(text).is_ok()
+    {
+        return vec![Detection {
+            layout,
+            confidence: 1.0,
+        }];
+    }
+    let nums = crate::split_numbers(text);
+    let weights = RawStringType::iter()
+        .filter(|layout| {
+            layout.number_count() == nums.len()
+                && !matches!(layout, RawStringType::Tf2Echo | RawStringType::RosYaml)
+        })
+        .filter_map(|layout| {
+            let error = layout.fit_error(&nums)?;
+            Some((
+                layout,
+                layout.prior(&nums) * (-error / DETECT_TOLERANCE).exp(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let total = weights.iter().map(|(_, weight)| weight).sum::<f64>();
+    if total <= 0.0 {
+        // Every candidate is far off; fall back to equal shares rather than dividing by zero.
+        let share = 1.0 / weights.len() as f64;
+        return weights
+            .into_iter()
+            .map(|(layout, _)| Detection {
+                layout,
+                confidence: share,
+            })
+            .collect();
+    }
+    let mut detections = weights
+        .into_iter()
+        .map(|(layout, weight)| Detection {
+            layout,
+            confidence: weight / total,
+        })
+        .collect::<Vec<_>>();
+    detections.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    detections
+}
+
+/// Formats numbers precisely enough to round-trip through `split_numbers`, `per_line` a line.
+pub fn format_numbers(nums: &[f64], per_line: usize) -> String {
+    nums.chunks(per_line)
+        .map(|line| {
+            line.iter()
+                .map(|x| format!("{x:.9}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let quat = na::UnitQuaternion::from_euler_angles(0.3, -0.2, 1.1);
+        let translation = na::Vector3::new(1.0, 2.0, 3.0);
+        for layout in [RawStringType::RowMajor4x4, RawStringType::ColumnMajor4x4] {
+            let detections = detect(&layout.format_transform(&quat, &translation));
+            let best = detections.first().expect("16 numbers fit a 4x4 layout");
+            assert_eq!(best.layout, layout, "the [0 0 0 1] row decides");
+            assert!(best.confidence > 0.99, "{layout:?} is unambiguous");
+        }
+        let detections = detect("0.1 0.2 0.3 0.9");
+        assert_eq!(detections.len(), 2, "both quaternion orders");
+        assert!(
+            detections
+                .iter()
+                .all(|detection| detection.confidence > AMBIGUOUS_CONFIDENCE),
+            "quaternion order is flagged as ambiguous"
+        );
+        let yaml = RawStringType::RosYaml.format_transform(&quat, &translation);
+        assert_eq!(
+            detect(&yaml).first().map(|detection| detection.layout),
+            Some(RawStringType::RosYaml),
+            "ROS YAML is recognized by its keys"
+        );
+    }
+}
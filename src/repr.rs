@@ -0,0 +1,187 @@
+//! Rotation representations as flat number lists, shared by the language bindings.
+//!
+//! Every binding goes through these functions, so scripts read and write exactly what the GUI
+//! shows: the same quaternion orders, angle-axis with the angle first, row-major matrices unless
+//! column-major is asked for, and Euler angles in radians in a chosen sequence and frame.
+
+use crate::euler::{EulerFrame, EulerSequence};
+use crate::raw::RawStringType;
+use nalgebra as na;
+#[cfg(any(test, feature = "python", target_arch = "wasm32"))]
+use strum::IntoEnumIterator;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum Representation {
+    QuaternionWXYZ,
+    QuaternionXYZW,
+    /// Angle in radians, then the axis.
+    AngleAxis,
+    /// Axis times angle in radians.
+    RotationVector,
+    Mrp,
+    Gibbs,
+    /// Three angles in radians, in the given sequence and frame.
+    Euler,
+    /// Row-major 3x3.
+    RotationMatrix,
+    /// Column-major 3x3, the storage order of Eigen and GLM.
+    RotationMatrixColumnMajor,
+}
+
+/// The variant of `E` whose name matches `name`, ignoring case and underscores.
+///
+/// # Errors
+/// When no variant matches; the message lists them.
+#[cfg(any(test, feature = "python", target_arch = "wasm32"))]
+pub fn by_name<E: IntoEnumIterator + std::fmt::Debug>(name: &str) -> anyhow::Result<E> {
+    let normalize = |name: &str| name.replace('_', "").to_ascii_lowercase();
+    let wanted = normalize(name);
+    E::iter()
+        .find(|variant| normalize(&format!("{variant:?}")) == wanted)
+        .ok_or_else(|| {
+            let names = E::iter()
+                .map(|variant| format!("{variant:?}"))
+                .collect::<Vec<_>>();
+            anyhow::anyhow!(
+                "unknown name {name:?}, expected one of {}",
+                names.join(", ")
+            )
+        })
+}
+
+impl Representation {
+    pub fn number_count(self) -> usize {
+        match self {
+            Self::QuaternionWXYZ | Self::QuaternionXYZW | Self::AngleAxis => 4,
+            Self::RotationVector | Self::Mrp | Self::Gibbs | Self::Euler => 3,
+            Self::RotationMatrix | Self::RotationMatrixColumnMajor => 9,
+        }
+    }
+
+    /// The rotation `values` describe in this representation.
+    ///
+    /// # Errors
+    /// When `values` has the wrong length, or the quaternion or axis is zero.
+    pub fn quat(
+        self,
+        values: &[f64],
+        sequence: EulerSequence,
+        frame: EulerFrame,
+    ) -> anyhow::Result<na::UnitQuaternion<f64>> {
+        if values.len() != self.number_count() {
+            anyhow::bail!(
+                "{self:?} takes {} numbers, got {}",
+                self.number_count(),
+                values.len()
+            );
+        }
+        let vector = |values: &[f64]| na::Vector3::from_iterator(values.iter().copied());
+        let quat = |w: f64, x: f64, y: f64, z: f64| {
+            na::UnitQuaternion::try_new(na::Quaternion::new(w, x, y, z), f64::EPSILON)
+                .ok_or_else(|| anyhow::anyhow!("the quaternion is zero"))
+        };
+        Ok(match (self, values) {
+            (Self::QuaternionWXYZ, &[w, x, y, z]) | (Self::QuaternionXYZW, &[x, y, z, w]) => {
+                quat(w, x, y, z)?
+            }
+            (Self::AngleAxis, &[angle, x, y, z]) => {
+                let axis = na::Unit::try_new(na::Vector3::new(x, y, z), f64::EPSILON)
+                    .ok_or_else(|| anyhow::anyhow!("the axis is zero"))?;
+                na::UnitQuaternion::from_axis_angle(&axis, angle)
+            }
+            (Self::RotationVector, _) => na::UnitQuaternion::from_scaled_axis(vector(values)),
+            (Self::Mrp, _) => crate::convert::mrp_to_quat(&vector(values)),
+            (Self::Gibbs, _) => crate::convert::gibbs_to_quat(&vector(values)),
+            (Self::Euler, &[a, b, c]) => crate::euler::to_quat(sequence, frame, [a, b, c]),
+            (Self::RotationMatrix, _) => na::UnitQuaternion::from_rotation_matrix(
+                &na::Rotation3::from_matrix(&na::Matrix3::from_row_slice(values)),
+            ),
+            (Self::RotationMatrixColumnMajor, _) => na::UnitQuaternion::from_rotation_matrix(
+                &na::Rotation3::from_matrix(&na::Matrix3::from_column_slice(values)),
+            ),
+            _ => anyhow::bail!("{self:?} takes {} numbers", self.number_count()),
+        })
+    }
+
+    /// The numbers of `quat` in this representation.
+    pub fn values(
+        self,
+        quat: &na::UnitQuaternion<f64>,
+        sequence: EulerSequence,
+        frame: EulerFrame,
+    ) -> Vec<f64> {
+        match self {
+            Self::QuaternionWXYZ => vec![quat.w, quat.i, quat.j, quat.k],
+            Self::QuaternionXYZW => quat.coords.as_slice().to_vec(),
+            Self::AngleAxis => {
+                let (axis, angle) = quat.axis_angle().unwrap_or((na::Vector3::x_axis(), 0.0));
+                vec![angle, axis.x, axis.y, axis.z]
+            }
+            Self::RotationVector => quat.scaled_axis().as_slice().to_vec(),
+            Self::Mrp => crate::convert::quat_to_mrp(quat).as_slice().to_vec(),
+            Self::Gibbs => crate::convert::quat_to_gibbs(quat).as_slice().to_vec(),
+            Self::Euler => crate::euler::from_quat(sequence, frame, quat)
+                .angles
+                .to_vec(),
+            Self::RotationMatrix => quat
+                .to_rotation_matrix()
+                .matrix()
+                .transpose()
+                .as_slice()
+                .to_vec(),
+            Self::RotationMatrixColumnMajor => {
+                quat.to_rotation_matrix().matrix().as_slice().to_vec()
+            }
+        }
+    }
+}
+
+/// A rotation and, for layouts that carry one, a translation read from raw text.
+///
+/// # Errors
+/// When the text does not hold one rotation in `layout`.
+pub fn parse_raw(
+    text: &str,
+    layout: RawStringType,
+) -> anyhow::Result<(na::UnitQuaternion<f64>, Option<na::Vector3<f64>>)> {
+    let nums = layout.numbers(text)?;
+    Ok((layout.parse(&nums)?, layout.translation(&nums)))
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator as _;
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let quat = na::UnitQuaternion::from_euler_angles(0.4, -0.3, 2.0);
+        for repr in Representation::iter() {
+            let values = repr.values(&quat, EulerSequence::ZYZ, EulerFrame::Extrinsic);
+            let back = repr
+                .quat(&values, EulerSequence::ZYZ, EulerFrame::Extrinsic)
+                .expect("valid values");
+            assert!(back.angle_to(&quat) < 1e-9, "{repr:?} roundtrip");
+        }
+    }
+
+    #[test]
+    fn test_by_name() {
+        for repr in Representation::iter() {
+            let name = format!("{repr:?}").to_lowercase();
+            assert_eq!(by_name::<Representation>(&name).ok(), Some(repr), "{name}");
+        }
+        let layout = by_name::<RawStringType>("row_major_4x4").expect("underscores are ignored");
+        let text = "1 0 0 1\n0 1 0 2\n0 0 1 3\n0 0 0 1";
+        let (quat, translation) = parse_raw(text, layout).expect("valid matrix");
+        assert!(quat.angle() < 1e-12, "identity rotation");
+        assert_eq!(
+            translation,
+            Some(na::Vector3::new(1.0, 2.0, 3.0)),
+            "translation"
+        );
+    }
+}
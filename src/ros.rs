@@ -0,0 +1,255 @@
+//! ROS text output: `tf2_echo` transforms and `geometry_msgs` YAML as printed by `topic echo`.
+//!
+//! Both parse into `[tx, ty, tz, qx, qy, qz, qw]`, or `[qx, qy, qz, qw]` for messages without a
+//! translation, so the timestamps, RPY lines and matrices around them are never mistaken for
+//! pose numbers.
+
+use nalgebra as na;
+
+/// Keys whose `x y z` children are a translation.
+const TRANSLATION_KEYS: [&str; 2] = ["translation", "position"];
+/// Keys whose `x y z w` children are a quaternion.
+const ROTATION_KEYS: [&str; 2] = ["rotation", "orientation"];
+
+/// The numbers between the first `[` and `]` of `line`.
+fn bracketed(line: &str) -> Option<Vec<f64>> {
+    let start = line.find('[')?;
+    let end = start + line.get(start..)?.find(']')?;
+    Some(crate::split_numbers(line.get(start + 1..end)?))
+}
+
+/// Reads the `Translation:` and `in Quaternion` lines of `ros2 run tf2_ros tf2_echo` output.
+///
+/// # Errors
+/// When there is no quaternion line, or a line has the wrong number of values.
+pub fn parse_tf2_echo(text: &str) -> anyhow::Result<Vec<f64>> {
+    let mut translation = None;
+    let mut quaternion = None;
+    for line in text.lines() {
+        if line.contains("Translation") {
+            translation = bracketed(line);
+        } else if line.contains("Quaternion") {
+            quaternion = bracketed(line);
+        }
+        if quaternion.is_some() && translation.is_some() {
+            break;
+        }
+    }
+    let Some(quaternion) = quaternion else {
+        anyhow::bail!("no \"in Quaternion [x, y, z, w]\" line");
+    };
+    if quaternion.len() != 4 {
+        anyhow::bail!("the quaternion has {} values, not 4", quaternion.len());
+    }
+    Ok(match translation {
+        Some(translation) if translation.len() == 3 => [translation, quaternion].concat(),
+        Some(translation) => {
+            anyhow::bail!("the translation has {} values, not 3", translation.len())
+        }
+        None => quaternion,
+    })
+}
+
+/// Reads the first message of `geometry_msgs` YAML: `Transform`, `Pose`, `Quaternion`, or any
+/// message containing one of them, such as `TransformStamped`. Block and single-line flow
+/// mappings (`rotation: {x: 0, y: 0, z: 0, w: 1}`) are both accepted.
+///
+/// Only `x y z w` under `rotation` or `orientation`, or at the top level, make the quaternion,
+/// so the twist of an `Odometry` message is skipped; the first complete one is kept.
+///
+/// # Errors
+/// When a quaternion component is missing or a value is not a number.
+pub fn parse_yaml(text: &str) -> anyhow::Result<Vec<f64>> {
+    // Indentation and name of the mappings enclosing the current line.
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    let mut translation = [None; 3];
+    let mut rotation = [None; 4];
+    for line in text.lines() {
+        if line.trim() == "---" {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let indent = key.len() - key.trim_start().len();
+        let key = key.trim().trim_start_matches("- ");
+        let value = value.trim();
+        parents.retain(|&(parent_indent, _)| parent_indent < indent);
+        let pairs = if value.is_empty() {
+            parents.push((indent, key));
+            continue;
+        } else if let Some(inner) = value.strip_prefix('{').and_then(|v| v.strip_suffix('}')) {
+            parents.push((indent, key));
+            inner
+                .split(',')
+                .filter_map(|pair| pair.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect::<Vec<_>>()
+        } else {
+            vec![(key, value)]
+        };
+        let parent = parents.last().map(|&(_, parent)| parent);
+        let in_translation = parent.is_some_and(|parent| TRANSLATION_KEYS.contains(&parent))
+            && translation.contains(&None);
+        let in_rotation =
+            parent.is_none_or(|parent| ROTATION_KEYS.contains(&parent)) && rotation.contains(&None);
+        for (key, value) in pairs {
+            let slot = match (in_translation, in_rotation, key) {
+                (true, _, "x") => translation.get_mut(0),
+                (true, _, "y") => translation.get_mut(1),
+                (true, _, "z") => translation.get_mut(2),
+                (_, true, "x") => rotation.get_mut(0),
+                (_, true, "y") => rotation.get_mut(1),
+                (_, true, "z") => rotation.get_mut(2),
+                (_, true, "w") => rotation.get_mut(3),
+                _ => None,
+            };
+            if let Some(slot) = slot {
+                let number = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("{key}: {value:?} is not a number ({e})"))?;
+                *slot = Some(number);
+            }
+        }
+    }
+    let [Some(qx), Some(qy), Some(qz), Some(qw)] = rotation else {
+        anyhow::bail!("no rotation/orientation with x, y, z and w");
+    };
+    Ok(match translation {
+        [Some(tx), Some(ty), Some(tz)] => vec![tx, ty, tz, qx, qy, qz, qw],
+        _ => vec![qx, qy, qz, qw],
+    })
+}
+
+/// Splits repeated output into messages: YAML documents end at `---`, and each `tf2_echo`
+/// sample starts with `At time`.
+pub fn split_messages(text: &str) -> Vec<String> {
+    let mut messages = vec![String::new()];
+    for line in text.lines() {
+        let starts_sample = line.trim_start().starts_with("At time");
+        if line.trim() == "---" || starts_sample {
+            messages.push(String::new());
+        }
+        if let Some(message) = messages.last_mut()
+            && line.trim() != "---"
+        {
+            message.push_str(line);
+            message.push('\n');
+        }
+    }
+    messages.retain(|message| !message.trim().is_empty());
+    messages
+}
+
+pub fn format_tf2_echo(quat: &na::UnitQuaternion<f64>, translation: &na::Vector3<f64>) -> String {
+    let rpy = crate::urdf::quat_to_rpy(quat);
+    let list = |values: &[f64]| {
+        values
+            .iter()
+            .map(|x| format!("{x:.9}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "At time 0.0\n- Translation: [{}]\n- Rotation: in Quaternion [{}]\n- Rotation: in RPY (radian) [{}]\n- Rotation: in RPY (degree) [{}]",
+        list(translation.as_slice()),
+        list(quat.coords.as_slice()),
+        list(&rpy),
+        list(&rpy.map(f64::to_degrees)),
+    )
+}
+
+/// A `geometry_msgs/Transform` in the block style of `topic echo`.
+pub fn format_yaml(quat: &na::UnitQuaternion<f64>, translation: &na::Vector3<f64>) -> String {
+    format!(
+        "translation:\n  x: {:.9}\n  y: {:.9}\n  z: {:.9}\nrotation:\n  x: {:.9}\n  y: {:.9}\n  z: {:.9}\n  w: {:.9}",
+        translation.x, translation.y, translation.z, quat.i, quat.j, quat.k, quat.w
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tf2_echo() {
+        let text = "At time 1700000000.123456789
+- Translation: [1.000, 2.000, 3.000]
+- Rotation: in Quaternion (xyzw) [0.000, 0.000, 0.707, 0.707]
+- Rotation: in RPY (radian) [0.000, -0.000, 1.571]
+- Rotation: in RPY (degree) [0.000, -0.000, 90.000]
+- Matrix:
+  0.000 -1.000  0.000  1.000
+  1.000  0.000  0.000  2.000
+  0.000  0.000  1.000  3.000
+  0.000  0.000  0.000  1.000
+";
+        assert_eq!(
+            parse_tf2_echo(text).expect("valid output"),
+            vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.707, 0.707],
+            "translation then xyzw quaternion"
+        );
+    }
+
+    #[test]
+    fn test_yaml() {
+        let stamped = "header:
+  stamp:
+    sec: 1700000000
+    nanosec: 5
+  frame_id: map
+child_frame_id: base_link
+transform:
+  translation:
+    x: 1.0
+    y: 2.0
+    z: 3.0
+  rotation:
+    x: 0.0
+    y: 0.0
+    z: 0.6
+    w: 0.8
+---
+";
+        let pose =
+            "position: {x: 1.0, y: 2.0, z: 3.0}\norientation: {x: 0.0, y: 0.0, z: 0.6, w: 0.8}\n";
+        let odometry = "header:
+  frame_id: odom
+child_frame_id: base_link
+pose:
+  pose:
+    position:
+      x: 1.0
+      y: 2.0
+      z: 3.0
+    orientation:
+      x: 0.0
+      y: 0.0
+      z: 0.6
+      w: 0.8
+  covariance: [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+twist:
+  twist:
+    linear:
+      x: 0.5
+      y: 0.1
+      z: 0.0
+    angular:
+      x: 0.2
+      y: 0.3
+      z: 0.4
+";
+        for text in [stamped, pose, odometry] {
+            assert_eq!(
+                parse_yaml(text).expect("valid message"),
+                vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.6, 0.8],
+                "{text}"
+            );
+        }
+        assert_eq!(
+            parse_yaml("x: 0.0\ny: 0.0\nz: 0.0\nw: 1.0\n").expect("valid quaternion"),
+            vec![0.0, 0.0, 0.0, 1.0],
+            "a bare quaternion has no translation"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Rotations found by key name in JSON, YAML and TOML documents.
+
+use nalgebra as na;
+use serde_json::Value;
+use strum::IntoEnumIterator as _;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum DataFormat {
+    /// JSON, then TOML, then YAML, whichever parses first.
+    Auto,
+    Json,
+    Toml,
+    Yaml,
+}
+
+/// Keys holding a rotation as an array, compared case-insensitively.
+const ROTATION_KEYS: [&str; 7] = [
+    "rotation",
+    "orientation",
+    "quaternion",
+    "quat",
+    "q",
+    "attitude",
+    "r",
+];
+const RPY_KEYS: [&str; 3] = ["rpy", "euler", "euler_angles"];
+const ROTATION_VECTOR_KEYS: [&str; 3] = ["rotvec", "rotation_vector", "rvec"];
+const MATRIX_KEYS: [&str; 4] = ["matrix", "rotation_matrix", "transform", "pose"];
+const TRANSLATION_KEYS: [&str; 5] = ["translation", "position", "t", "xyz", "tvec"];
+
+/// How the importer reads arrays and angles that do not name their convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Conventions {
+    /// Quaternion arrays are `[x, y, z, w]` rather than `[w, x, y, z]`.
+    pub xyzw: bool,
+    /// Roll, pitch and yaw are in degrees. Axis-angle and rotation vector angles are always in
+    /// radians.
+    pub degrees: bool,
+}
+
+/// A rotation-like field of the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Dotted path to the field, `[i]` for array elements.
+    pub path: String,
+    /// How the field was read, e.g. "quaternion object".
+    pub kind: &'static str,
+    pub rotation: na::UnitQuaternion<f64>,
+    /// A sibling translation or position, or the translation of a 4x4 matrix.
+    pub translation: Option<na::Vector3<f64>>,
+}
+
+/// Parses `text` into a document tree.
+///
+/// # Errors
+/// When the text is not valid in `format` (in any of them for [`DataFormat::Auto`]).
+pub fn parse(format: DataFormat, text: &str) -> anyhow::Result<(DataFormat, Value)> {
+    Ok(match format {
+        DataFormat::Auto => {
+            let mut errors = Vec::new();
+            for format in DataFormat::iter().skip(1) {
+                match parse(format, text) {
+                    Ok(parsed) => return Ok(parsed),
+                    Err(e) => errors.push(format!("{format:?}: {e}")),
+                }
+            }
+            anyhow::bail!("not JSON, YAML or TOML\n{}", errors.join("\n"))
+        }
+        DataFormat::Json => (format, serde_json::from_str(text)?),
+        DataFormat::Yaml => (format, serde_yaml_ng::from_str(text)?),
+        DataFormat::Toml => (format, toml::from_str(text)?),
+    })
+}
+
+/// Numbers of a flat array, or of an array of arrays read row by row.
+fn numbers(value: &Value) -> Option<Vec<f64>> {
+    let array = value.as_array()?;
+    if array.iter().all(Value::is_array) {
+        return array
+            .iter()
+            .map(numbers)
+            .collect::<Option<Vec<_>>>()
+            .map(|rows| rows.concat());
+    }
+    array.iter().map(Value::as_f64).collect()
+}
+
+/// The field of `object` named `key`, ignoring case.
+fn field<'a>(object: &'a serde_json::Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+/// `[x, y, z]` or `{x, y, z}`.
+fn vector3(value: &Value) -> Option<na::Vector3<f64>> {
+    if let Some(object) = value.as_object() {
+        let get = |key: &str| field(object, key).and_then(Value::as_f64);
+        return Some(na::Vector3::new(get("x")?, get("y")?, get("z")?));
+    }
+    match numbers(value)?.as_slice() {
+        &[x, y, z] => Some(na::Vector3::new(x, y, z)),
+        _ => None,
+    }
+}
+
+/// A rotation in an object's own fields: `{w, x, y, z}` (or `qw`...), `{roll, pitch, yaw}`, or
+/// `{axis, angle}`. Zero quaternions are skipped.
+fn rotation_object(
+    object: &serde_json::Map<String, Value>,
+    conventions: Conventions,
+) -> Option<(&'static str, na::UnitQuaternion<f64>)> {
+    let get = |key: &str| field(object, key).and_then(Value::as_f64);
+    let scale = if conventions.degrees {
+        1f64.to_radians()
+    } else {
+        1.0
+    };
+    let quaternion =
+        |[w, x, y, z]: [&str; 4]| Some(na::Quaternion::new(get(w)?, get(x)?, get(y)?, get(z)?));
+    if let Some(quat) =
+        quaternion(["w", "x", "y", "z"]).or_else(|| quaternion(["qw", "qx", "qy", "qz"]))
+    {
+        let quat = na::UnitQuaternion::try_new(quat, f64::EPSILON)?;
+        return Some(("quaternion object", quat));
+    }
+    if let (Some(roll), Some(pitch), Some(yaw)) = (get("roll"), get("pitch"), get("yaw")) {
+        return Some((
+            "roll/pitch/yaw object",
+            crate::urdf::rpy_to_quat([roll * scale, pitch * scale, yaw * scale]),
+        ));
+    }
+    let axis = field(object, "axis").and_then(vector3)?;
+    let angle = get("angle")?;
+    let axis = na::Unit::try_new(axis, f64::EPSILON)?;
+    Some((
+        "axis-angle object",
+        na::UnitQuaternion::from_axis_angle(&axis, angle),
+    ))
+}
+
+/// A rotation held in an array under a telling key, found at `path`.
+fn rotation_array(
+    key: &str,
+    value: &Value,
+    path: &str,
+    conventions: Conventions,
+) -> Option<Candidate> {
+    let key = key.to_ascii_lowercase();
+    let is = |keys: &[&str]| keys.contains(&key.as_str());
+    let nums = numbers(value)?;
+    let scale = if conventions.degrees {
+        1f64.to_radians()
+    } else {
+        1.0
+    };
+    let matrix = |matrix: &na::Matrix3<f64>| {
+        na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix(matrix))
+    };
+    let mut translation = None;
+    let (kind, rotation) = match *nums.as_slice() {
+        [a, b, c, d] if is(&ROTATION_KEYS) => {
+            let (w, x, y, z) = if conventions.xyzw {
+                (d, a, b, c)
+            } else {
+                (a, b, c, d)
+            };
+            (
+                "quaternion array",
+                na::UnitQuaternion::try_new(na::Quaternion::new(w, x, y, z), f64::EPSILON)?,
+            )
+        }
+        [roll, pitch, yaw] if is(&RPY_KEYS) => (
+            "roll/pitch/yaw array",
+            crate::urdf::rpy_to_quat([roll * scale, pitch * scale, yaw * scale]),
+        ),
+        [x, y, z] if is(&ROTATION_VECTOR_KEYS) => (
+            "rotation vector",
+            na::UnitQuaternion::from_scaled_axis(na::Vector3::new(x, y, z)),
+        ),
+        _ if nums.len() == 9 && (is(&ROTATION_KEYS) || is(&MATRIX_KEYS)) => (
+            "row-major 3x3 matrix",
+            matrix(&na::Matrix3::from_row_slice(&nums)),
+        ),
+        _ if nums.len() == 16 && (is(&ROTATION_KEYS) || is(&MATRIX_KEYS)) => {
+            let transform = na::Matrix4::from_row_slice(&nums);
+            translation = Some(transform.fixed_view::<3, 1>(0, 3).into_owned());
+            (
+                "row-major 4x4 matrix",
+                matrix(&transform.fixed_view::<3, 3>(0, 0).into_owned()),
+            )
+        }
+        _ => return None,
+    };
+    Some(Candidate {
+        path: path.to_owned(),
+        kind,
+        rotation,
+        translation,
+    })
+}
+
+/// The sibling translation of a rotation field in `object`.
+fn sibling_translation(object: &serde_json::Map<String, Value>) -> Option<na::Vector3<f64>> {
+    TRANSLATION_KEYS
+        .iter()
+        .find_map(|key| field(object, key).and_then(vector3))
+}
+
+fn walk(value: &Value, path: &str, conventions: Conventions, found: &mut Vec<Candidate>) {
+    match value {
+        Value::Object(object) => {
+            if let Some((kind, rotation)) = rotation_object(object, conventions) {
+                found.push(Candidate {
+                    path: path.to_owned(),
+                    kind,
+                    rotation,
+                    translation: None,
+                });
+            }
+            for (key, child) in object {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                if let Some(mut candidate) = rotation_array(key, child, &child_path, conventions) {
+                    if candidate.translation.is_none() {
+                        candidate.translation = sibling_translation(object);
+                    }
+                    found.push(candidate);
+                } else if let Some(child_object) = child.as_object()
+                    && rotation_object(child_object, conventions).is_some()
+                {
+                    // Attach the translation next to a rotation object once it is found below.
+                    let before = found.len();
+                    walk(child, &child_path, conventions, found);
+                    if let Some(candidate) = found.get_mut(before)
+                        && candidate.path == child_path
+                    {
+                        candidate.translation = sibling_translation(object);
+                    }
+                    continue;
+                }
+                walk(child, &child_path, conventions, found);
+            }
+        }
+        Value::Array(array) => {
+            for (index, child) in array.iter().enumerate() {
+                walk(child, &format!("{path}[{index}]"), conventions, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Every rotation-like field of `document`, in document order.
+pub fn find_rotations(document: &Value, conventions: Conventions) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    walk(document, "", conventions, &mut found);
+    found
+}
+
+/// State of the structured import panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct StructuredTool {
+    format: DataFormat,
+    conventions: Conventions,
+    #[serde(skip)]
+    text: String,
+    #[serde(skip)]
+    candidates: Option<anyhow::Result<(DataFormat, Vec<Candidate>)>>,
+}
+
+impl Default for StructuredTool {
+    fn default() -> Self {
+        Self {
+            format: DataFormat::Auto,
+            conventions: Conventions {
+                xyzw: false,
+                degrees: true,
+            },
+            text: String::new(),
+            candidates: None,
+        }
+    }
+}
+
+impl StructuredTool {
+    /// Returns the field the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<Candidate> {
+        if let Some(dropped) = crate::dropped_text(ui.ctx(), &[".json", ".yaml", ".yml", ".toml"]) {
+            match dropped {
+                Ok(text) => {
+                    self.text = text;
+                    self.candidates = None;
+                }
+                Err(e) => self.candidates = Some(Err(e)),
+            }
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("format")
+                .selected_text(format!("{:?}", self.format))
+                .show_ui(ui, |ui| {
+                    for format in DataFormat::iter() {
+                        ui.selectable_value(&mut self.format, format, format!("{format:?}"));
+                    }
+                });
+            let changed = ui
+                .checkbox(&mut self.conventions.xyzw, "arrays are [x, y, z, w]")
+                .changed()
+                | ui.checkbox(&mut self.conventions.degrees, "roll/pitch/yaw in degrees")
+                    .changed();
+            if ui.button("find rotations").clicked() || changed {
+                self.candidates = None;
+            }
+        });
+        let edited = ui
+            .add(
+                egui::TextEdit::multiline(&mut self.text)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(6)
+                    .hint_text("paste JSON, YAML or TOML, or drop a file onto the window"),
+            )
+            .changed();
+        if edited || (self.candidates.is_none() && !self.text.trim().is_empty()) {
+            self.candidates =
+                Some(parse(self.format, &self.text).map(|(format, document)| {
+                    (format, find_rotations(&document, self.conventions))
+                }));
+        }
+        let (format, candidates) = match self.candidates.as_ref()? {
+            Ok(found) => found,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{e:#}"));
+                return None;
+            }
+        };
+        if candidates.is_empty() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("No rotation-like fields in this {format:?} document"),
+            );
+            return None;
+        }
+        let mut loaded = None;
+        egui::Grid::new("structured_candidates")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in [
+                    "field",
+                    "read as",
+                    "quaternion [w, x, y, z]",
+                    "translation",
+                    "",
+                ] {
+                    ui.label(egui::RichText::new(header).strong());
+                }
+                ui.end_row();
+                for candidate in candidates {
+                    let quat = candidate.rotation;
+                    ui.monospace(&candidate.path);
+                    ui.label(candidate.kind);
+                    ui.label(format!(
+                        "[{:.4}, {:.4}, {:.4}, {:.4}]",
+                        quat.w, quat.i, quat.j, quat.k
+                    ));
+                    ui.label(candidate.translation.map_or_else(String::new, |t| {
+                        format!("[{:.4}, {:.4}, {:.4}]", t.x, t.y, t.z)
+                    }));
+                    if ui.small_button("load").clicked() {
+                        loaded = Some(candidate.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        loaded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_rotations() {
+        let conventions = Conventions {
+            xyzw: false,
+            degrees: true,
+        };
+        let json = r#"{"camera": {"position": [1, 2, 3], "orientation": {"w": 1, "x": 0, "y": 0, "z": 0}},
+            "imu": {"roll": 0, "pitch": 0, "yaw": 90}, "unset": {"quat": [0, 0, 0, 0]}}"#;
+        let yaml = "camera:\n  position: {x: 1, y: 2, z: 3}\n  orientation: [1, 0, 0, 0]\nimu:\n  rpy: [0, 0, 90]\n";
+        let toml = "[camera]\nposition = [1, 2, 3]\nrotation = [1, 0, 0, 0]\n[imu]\nroll = 0\npitch = 0\nyaw = 90\n";
+        let quarter_turn = na::UnitQuaternion::from_axis_angle(
+            &na::Vector3::z_axis(),
+            std::f64::consts::FRAC_PI_2,
+        );
+        for (format, text) in [
+            (DataFormat::Json, json),
+            (DataFormat::Yaml, yaml),
+            (DataFormat::Toml, toml),
+        ] {
+            let (detected, document) = parse(DataFormat::Auto, text).expect("valid document");
+            assert_eq!(detected, format, "auto picks the right parser");
+            let found = find_rotations(&document, conventions);
+            let [camera, imu] = found.as_slice() else {
+                panic!("{format:?}: two rotations, found {found:?}");
+            };
+            assert!(
+                camera.rotation.angle() < 1e-12,
+                "{format:?} camera is identity"
+            );
+            assert_eq!(
+                camera.translation,
+                Some(na::Vector3::new(1.0, 2.0, 3.0)),
+                "{format:?} camera position"
+            );
+            assert!(
+                imu.rotation.angle_to(&quarter_turn) < 1e-12,
+                "{format:?} imu yaw"
+            );
+        }
+        let (_, document) = parse(
+            DataFormat::Json,
+            r#"{"axis": [0, 0, 1], "angle": 1.5707963267948966}"#,
+        )
+        .expect("valid document");
+        let found = find_rotations(&document, conventions);
+        assert!(
+            found
+                .first()
+                .is_some_and(|axis_angle| axis_angle.rotation.angle_to(&quarter_turn) < 1e-12),
+            "axis-angle stays in radians, found {found:?}"
+        );
+    }
+}
@@ -0,0 +1,739 @@
+//! Pose trajectories in the TUM, KITTI, `EuRoC` and COLMAP text formats.
+
+use anyhow::Context as _;
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+use crate::euler::{EulerFrame, EulerSequence};
+
+/// Height of the pose table before it scrolls.
+const TABLE_HEIGHT: f32 = 300.0;
+/// Height of each plot.
+const PLOT_HEIGHT: f32 = 160.0;
+/// Names of the velocity and bias columns of `EuRoC` ground truth, which follow the pose.
+const EUROC_EXTRA_HEADER: &str = ",v_RS_R_x [m s^-1],v_RS_R_y [m s^-1],v_RS_R_z [m s^-1],b_w_RS_S_x [rad s^-1],b_w_RS_S_y [rad s^-1],b_w_RS_S_z [rad s^-1],b_a_RS_S_x [m s^-2],b_a_RS_S_y [m s^-2],b_a_RS_S_z [m s^-2]";
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum TrajectoryFormat {
+    /// `t tx ty tz qx qy qz qw` per line, time in seconds.
+    Tum,
+    /// Row-major 3x4 `[R | t]` per line, no time.
+    Kitti,
+    /// `EuRoC` ground-truth CSV: `t, px, py, pz, qw, qx, qy, qz, ...`, time in nanoseconds.
+    Euroc,
+    /// COLMAP `images.txt`: `IMAGE_ID QW QX QY QZ TX TY TZ CAMERA_ID NAME`, then a line of 2D
+    /// points. COLMAP stores world-to-camera poses; they are inverted on import and export.
+    Colmap,
+}
+
+/// What the plots use as their horizontal axis.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum PlotAxis {
+    Index,
+    /// Seconds; poses without a time use their index.
+    Time,
+}
+
+/// One pose of a trajectory: the body (or camera) frame in the world frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StampedPose {
+    /// Seconds.
+    pub time: Option<f64>,
+    /// COLMAP image name.
+    pub name: Option<String>,
+    /// COLMAP `IMAGE_ID`.
+    pub image_id: Option<u32>,
+    /// COLMAP `CAMERA_ID`.
+    pub camera_id: Option<u32>,
+    /// Written back unchanged: the COLMAP 2D point line, or the `EuRoC` columns after the
+    /// quaternion.
+    pub extra: Option<String>,
+    pub pose: na::Isometry3<f64>,
+}
+
+fn pose_from(translation: [f64; 3], quat: na::Quaternion<f64>) -> na::Isometry3<f64> {
+    na::Isometry3::from_parts(
+        na::Vector3::from(translation).into(),
+        na::UnitQuaternion::from_quaternion(quat),
+    )
+}
+
+/// Numbers of one line split on whitespace and commas.
+fn numbers(line: &str, line_number: usize) -> anyhow::Result<Vec<f64>> {
+    line.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            field
+                .parse()
+                .with_context(|| format!("line {line_number}: {field:?} is not a number"))
+        })
+        .collect()
+}
+
+fn parse_line(
+    format: TrajectoryFormat,
+    line: &str,
+    line_number: usize,
+) -> anyhow::Result<StampedPose> {
+    let nums = numbers(line, line_number)?;
+    let wrong = |expected: &str| {
+        anyhow::anyhow!(
+            "line {line_number}: {format:?} needs {expected} numbers, got {}",
+            nums.len()
+        )
+    };
+    Ok(match format {
+        TrajectoryFormat::Tum => {
+            let &[t, tx, ty, tz, qx, qy, qz, qw] = nums.as_slice() else {
+                return Err(wrong("8"));
+            };
+            StampedPose {
+                time: Some(t),
+                name: None,
+                image_id: None,
+                camera_id: None,
+                extra: None,
+                pose: pose_from([tx, ty, tz], na::Quaternion::new(qw, qx, qy, qz)),
+            }
+        }
+        TrajectoryFormat::Kitti => {
+            if nums.len() != 12 {
+                return Err(wrong("12"));
+            }
+            let matrix = na::Matrix3x4::from_row_slice(&nums);
+            let rotation = na::Rotation3::from_matrix(&matrix.fixed_columns::<3>(0).into_owned());
+            StampedPose {
+                time: None,
+                name: None,
+                image_id: None,
+                camera_id: None,
+                extra: None,
+                pose: na::Isometry3::from_parts(
+                    matrix.column(3).into_owned().into(),
+                    na::UnitQuaternion::from_rotation_matrix(&rotation),
+                ),
+            }
+        }
+        TrajectoryFormat::Euroc => {
+            // Velocities and biases may follow.
+            let &[t, px, py, pz, qw, qx, qy, qz, ..] = nums.as_slice() else {
+                return Err(wrong("at least 8"));
+            };
+            let extra = line.split(',').skip(8).collect::<Vec<_>>().join(",");
+            StampedPose {
+                time: Some(t * 1e-9),
+                name: None,
+                image_id: None,
+                camera_id: None,
+                extra: (!extra.trim().is_empty()).then_some(extra),
+                pose: pose_from([px, py, pz], na::Quaternion::new(qw, qx, qy, qz)),
+            }
+        }
+        TrajectoryFormat::Colmap => anyhow::bail!("COLMAP images are parsed by parse_colmap"),
+    })
+}
+
+/// Image lines of `images.txt` with their index, each with the 2D point line after it.
+fn colmap_images(text: &str) -> Vec<((usize, &str), Option<&str>)> {
+    let lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>();
+    lines
+        .chunks(2)
+        .filter_map(|chunk| Some((*chunk.first()?, chunk.get(1).map(|&(_, points)| points))))
+        .filter(|((_, line), _)| !line.trim().is_empty())
+        .collect()
+}
+
+fn parse_colmap(text: &str) -> anyhow::Result<Vec<StampedPose>> {
+    colmap_images(text)
+        .into_iter()
+        .map(|((index, line), points)| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (Some(&image_id), Some(values), Some(&camera_id), Some(name)) = (
+                fields.first(),
+                fields.get(1..8),
+                fields.get(8),
+                fields.get(9..),
+            ) else {
+                anyhow::bail!("line {}: COLMAP image lines have 10 fields", index + 1);
+            };
+            let id = |field: &str| {
+                field
+                    .parse()
+                    .with_context(|| format!("line {}: {field:?} is not an ID", index + 1))
+            };
+            let nums = numbers(&values.join(" "), index + 1)?;
+            let &[qw, qx, qy, qz, tx, ty, tz] = nums.as_slice() else {
+                anyhow::bail!("line {}: expected qw qx qy qz tx ty tz", index + 1);
+            };
+            Ok(StampedPose {
+                time: None,
+                name: Some(name.join(" ")),
+                image_id: Some(id(image_id)?),
+                camera_id: Some(id(camera_id)?),
+                extra: points.map(str::to_owned),
+                pose: pose_from([tx, ty, tz], na::Quaternion::new(qw, qx, qy, qz)).inverse(),
+            })
+        })
+        .collect()
+}
+
+/// Pose lines of `text` with their index, skipping headers and blank lines.
+fn pose_lines(format: TrajectoryFormat, text: &str) -> Vec<(usize, &str)> {
+    if format == TrajectoryFormat::Colmap {
+        return colmap_images(text)
+            .into_iter()
+            .map(|(image, _)| image)
+            .collect();
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim_start();
+            // `EuRoC` headers start with '#', or with a letter in some exports.
+            !line.is_empty()
+                && !line.starts_with('#')
+                && !line.starts_with(|c: char| c.is_alphabetic())
+        })
+        .collect()
+}
+
+/// # Errors
+/// When a line has the wrong number of fields or a field is not a number.
+pub fn parse(format: TrajectoryFormat, text: &str) -> anyhow::Result<Vec<StampedPose>> {
+    if format == TrajectoryFormat::Colmap {
+        return parse_colmap(text);
+    }
+    pose_lines(format, text)
+        .into_iter()
+        .map(|(index, line)| parse_line(format, line, index + 1))
+        .collect()
+}
+
+/// Enforces quaternion sign continuity along the trajectory; see
+/// [`crate::interp::make_continuous`]. Returns how many poses were flipped.
+pub fn make_continuous(poses: &mut [StampedPose]) -> usize {
+    let mut quats = poses
+        .iter()
+        .map(|stamped| stamped.pose.rotation)
+        .collect::<Vec<_>>();
+    let flipped = crate::interp::make_continuous(&mut quats);
+    for (stamped, quat) in poses.iter_mut().zip(quats) {
+        stamped.pose.rotation = quat;
+    }
+    flipped
+}
+
+/// [`make_continuous`] on trajectory text, negating only the quaternion fields of the flipped
+/// samples so that everything else in the file stays as it was. KITTI rows hold rotation
+/// matrices, which have no sign to flip. Returns the text and how many samples were flipped.
+///
+/// # Errors
+/// When the text does not parse as `format`.
+pub fn make_text_continuous(
+    format: TrajectoryFormat,
+    text: &str,
+) -> anyhow::Result<(String, usize)> {
+    let quat_fields = match format {
+        TrajectoryFormat::Tum | TrajectoryFormat::Euroc => 4..8,
+        TrajectoryFormat::Colmap => 1..5,
+        TrajectoryFormat::Kitti => return Ok((text.to_owned(), 0)),
+    };
+    let mut poses = parse(format, text)?;
+    let before = poses
+        .iter()
+        .map(|stamped| stamped.pose.rotation)
+        .collect::<Vec<_>>();
+    let flipped = make_continuous(&mut poses);
+    let flipped_lines = pose_lines(format, text)
+        .into_iter()
+        .zip(before.iter().zip(&poses))
+        .filter(|(_, (before, after))| before.coords.dot(&after.pose.rotation.coords) < 0.0)
+        .map(|((index, _), _)| index)
+        .collect::<std::collections::HashSet<_>>();
+    let field = regex::Regex::new(r"[^\s,]+").expect("Failed to compile regex");
+    let corrected = text
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if !flipped_lines.contains(&index) {
+                return line.to_owned();
+            }
+            let mut count = 0;
+            field
+                .replace_all(line, |captures: &regex::Captures<'_>| {
+                    let value = captures.get(0).map_or("", |value| value.as_str());
+                    let index = count;
+                    count += 1;
+                    if !quat_fields.contains(&index) {
+                        value.to_owned()
+                    } else if let Some(positive) = value.strip_prefix('-') {
+                        positive.to_owned()
+                    } else {
+                        format!("-{}", value.strip_prefix('+').unwrap_or(value))
+                    }
+                })
+                .into_owned()
+        })
+        .collect();
+    Ok((corrected, flipped))
+}
+
+/// Poses without a time get their index, in seconds.
+pub fn format(format: TrajectoryFormat, poses: &[StampedPose]) -> String {
+    let lines = poses.iter().enumerate().map(|(index, stamped)| {
+        let time = stamped.time.unwrap_or(index as f64);
+        let quat = stamped.pose.rotation;
+        let t = stamped.pose.translation.vector;
+        match format {
+            TrajectoryFormat::Tum => format!(
+                "{time:.9} {:.9} {:.9} {:.9} {:.9} {:.9} {:.9} {:.9}",
+                t.x, t.y, t.z, quat.i, quat.j, quat.k, quat.w
+            ),
+            TrajectoryFormat::Kitti => {
+                let matrix = stamped.pose.to_homogeneous();
+                matrix
+                    .fixed_rows::<3>(0)
+                    .transpose()
+                    .iter()
+                    .map(|x| format!("{x:.9e}"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            TrajectoryFormat::Euroc => {
+                let extra = stamped
+                    .extra
+                    .as_ref()
+                    .map_or_else(String::new, |extra| format!(",{extra}"));
+                format!(
+                    "{:.0},{:.9},{:.9},{:.9},{:.9},{:.9},{:.9},{:.9}{extra}",
+                    time * 1e9,
+                    t.x,
+                    t.y,
+                    t.z,
+                    quat.w,
+                    quat.i,
+                    quat.j,
+                    quat.k
+                )
+            }
+            TrajectoryFormat::Colmap => {
+                let inverse = stamped.pose.inverse();
+                let (quat, t) = (inverse.rotation, inverse.translation.vector);
+                let name = stamped
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("{index:06}.png"));
+                let image_id = stamped.image_id.map_or(index + 1, |id| id as usize);
+                let camera_id = stamped.camera_id.unwrap_or(1);
+                let points = stamped.extra.as_deref().unwrap_or_default();
+                format!(
+                    "{image_id} {:.9} {:.9} {:.9} {:.9} {:.9} {:.9} {:.9} {camera_id} {name}\n{points}",
+                    quat.w,
+                    quat.i,
+                    quat.j,
+                    quat.k,
+                    t.x,
+                    t.y,
+                    t.z
+                )
+            }
+        }
+    });
+    let euroc_extra = if poses.iter().any(|stamped| stamped.extra.is_some()) {
+        EUROC_EXTRA_HEADER
+    } else {
+        ""
+    };
+    let header = match format {
+        TrajectoryFormat::Tum => "# timestamp tx ty tz qx qy qz qw\n".to_owned(),
+        TrajectoryFormat::Kitti => String::new(),
+        TrajectoryFormat::Euroc => format!(
+            "#timestamp [ns],p_RS_R_x [m],p_RS_R_y [m],p_RS_R_z [m],q_RS_w [],q_RS_x [],q_RS_y [],q_RS_z []{euroc_extra}\n"
+        ),
+        TrajectoryFormat::Colmap => {
+            "# IMAGE_ID, QW, QX, QY, QZ, TX, TY, TZ, CAMERA_ID, NAME\n# POINTS2D[] as (X, Y, POINT3D_ID)\n".to_owned()
+        }
+    };
+    format!("{header}{}", lines.collect::<Vec<_>>().join("\n"))
+}
+
+/// State of the trajectory panel. The trajectory itself is not persisted.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct TrajectoryTool {
+    import_format: TrajectoryFormat,
+    export_format: TrajectoryFormat,
+    #[serde(skip)]
+    text: String,
+    #[serde(skip)]
+    poses: Option<anyhow::Result<Vec<StampedPose>>>,
+    #[serde(skip)]
+    selected: Option<usize>,
+    #[serde(skip)]
+    export: String,
+    /// Samples flipped by the last sign fix.
+    #[serde(skip)]
+    flipped: Option<usize>,
+    plot_axis: PlotAxis,
+    /// Unwrap Euler angle jumps of 2π.
+    unwrap: bool,
+}
+
+impl Default for TrajectoryTool {
+    fn default() -> Self {
+        Self {
+            import_format: TrajectoryFormat::Tum,
+            export_format: TrajectoryFormat::Kitti,
+            text: String::new(),
+            poses: None,
+            selected: None,
+            export: String::new(),
+            flipped: None,
+            plot_axis: PlotAxis::Time,
+            unwrap: true,
+        }
+    }
+}
+
+fn format_combo(ui: &mut egui::Ui, label: &str, value: &mut TrajectoryFormat) {
+    egui::ComboBox::from_label(label)
+        .selected_text(format!("{value:?}"))
+        .show_ui(ui, |ui| {
+            for format in TrajectoryFormat::iter() {
+                ui.selectable_value(value, format, format!("{format:?}"));
+            }
+        });
+}
+
+impl TrajectoryTool {
+    /// Returns the pose of the row the user selected, to load into the main views. Euler angles
+    /// are plotted in the main view's `sequence`.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        sequence: EulerSequence,
+        frame: EulerFrame,
+    ) -> Option<na::Isometry3<f64>> {
+        if let Some(dropped) = crate::dropped_text(ui.ctx(), &[".txt", ".csv"]) {
+            match dropped {
+                Ok(text) => self.text = text,
+                Err(e) => self.poses = Some(Err(e)),
+            }
+        }
+        ui.horizontal(|ui| {
+            format_combo(ui, "format", &mut self.import_format);
+            if ui.button("import").clicked() {
+                self.poses = Some(parse(self.import_format, &self.text));
+                self.selected = None;
+                self.flipped = None;
+            }
+            if let Some(Ok(poses)) = &mut self.poses
+                && ui
+                    .button("fix signs")
+                    .on_hover_text("flip q to -q where it leaves the previous sample's hemisphere")
+                    .clicked()
+            {
+                self.flipped = Some(make_continuous(poses));
+            }
+        });
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .desired_rows(4)
+                .hint_text("paste a trajectory, or drop a .txt or .csv file onto the window"),
+        );
+        let poses = match &self.poses {
+            Some(Ok(poses)) => poses,
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+            None => return None,
+        };
+        ui.label(format!("{} poses (body in world)", poses.len()));
+        if let Some(flipped) = self.flipped {
+            ui.label(format!("Flipped {flipped} of {} quaternions", poses.len()));
+        }
+        let loaded = pose_table(ui, poses, &mut self.selected);
+        egui::CollapsingHeader::new("Plots").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label("x axis")
+                    .selected_text(format!("{:?}", self.plot_axis))
+                    .show_ui(ui, |ui| {
+                        for axis in PlotAxis::iter() {
+                            ui.selectable_value(&mut self.plot_axis, axis, format!("{axis:?}"));
+                        }
+                    });
+                ui.checkbox(&mut self.unwrap, "unwrap angles");
+            });
+            plots_ui(ui, poses, self.plot_axis, self.unwrap, sequence, frame);
+        });
+        ui.horizontal(|ui| {
+            format_combo(ui, "export format", &mut self.export_format);
+            if ui.button("export").clicked() {
+                self.export = format(self.export_format, poses);
+            }
+            if !self.export.is_empty() && ui.button("copy").clicked() {
+                ui.ctx().copy_text(self.export.clone());
+            }
+        });
+        if !self.export.is_empty() {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.export)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(4),
+            );
+        }
+        loaded
+    }
+}
+
+fn plots_ui(
+    ui: &mut egui::Ui,
+    poses: &[StampedPose],
+    axis: PlotAxis,
+    unwrap: bool,
+    sequence: EulerSequence,
+    frame: EulerFrame,
+) {
+    let x = poses
+        .iter()
+        .enumerate()
+        .map(|(index, stamped)| match axis {
+            PlotAxis::Index => index as f64,
+            PlotAxis::Time => stamped.time.unwrap_or(index as f64),
+        })
+        .collect::<Vec<_>>();
+    let quats = poses
+        .iter()
+        .map(|stamped| stamped.pose.rotation)
+        .collect::<Vec<_>>();
+
+    let angles = quats
+        .iter()
+        .map(|quat| crate::euler::from_quat(sequence, frame, quat).angles)
+        .collect::<Vec<_>>();
+    let euler = sequence
+        .axes()
+        .into_iter()
+        .enumerate()
+        .map(|(component, axis)| {
+            let values = angles
+                .iter()
+                .map(|angles| angles.get(component).copied().unwrap_or_default())
+                .collect::<Vec<_>>();
+            let values = if unwrap {
+                crate::plot::unwrap_angles(&values)
+            } else {
+                values
+            };
+            crate::plot::Series {
+                name: format!("{}{}", component + 1, crate::euler::axis_name(axis)),
+                values: values.into_iter().map(f64::to_degrees).collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    ui.label(format!("{frame:?} {sequence:?} Euler angles"));
+    crate::plot::line_plot(ui, &x, &euler, "deg", PLOT_HEIGHT);
+
+    let mut components = ["w", "x", "y", "z"].map(|name| crate::plot::Series {
+        name: format!("q{name}"),
+        values: Vec::with_capacity(quats.len()),
+    });
+    for quat in &quats {
+        for (series, value) in components.iter_mut().zip([quat.w, quat.i, quat.j, quat.k]) {
+            series.values.push(value);
+        }
+    }
+    ui.label("Quaternion components");
+    crate::plot::line_plot(ui, &x, &components, "1", PLOT_HEIGHT);
+
+    let rates = crate::plot::Series {
+        name: "|ω|".to_owned(),
+        values: crate::plot::angular_rates(&quats, &x)
+            .into_iter()
+            .map(f64::to_degrees)
+            .collect(),
+    };
+    ui.label("Angular rate between consecutive samples");
+    let unit = match axis {
+        PlotAxis::Index => "deg/sample",
+        PlotAxis::Time => "deg/s",
+    };
+    crate::plot::line_plot(ui, &x, &[rates], unit, PLOT_HEIGHT);
+}
+
+/// Scrollable table of `poses`; clicking a row selects it and returns its pose.
+fn pose_table(
+    ui: &mut egui::Ui,
+    poses: &[StampedPose],
+    selected: &mut Option<usize>,
+) -> Option<na::Isometry3<f64>> {
+    let mut loaded = None;
+    let headers = ["#", "time / name", "tx", "ty", "tz", "qw", "qx", "qy", "qz"];
+    egui_extras::TableBuilder::new(ui)
+        .striped(true)
+        .sense(egui::Sense::click())
+        .max_scroll_height(TABLE_HEIGHT)
+        .columns(egui_extras::Column::auto(), headers.len())
+        .header(20.0, |mut header| {
+            for title in headers {
+                header.col(|ui| {
+                    ui.strong(title);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(18.0, poses.len(), |mut row| {
+                let index = row.index();
+                let Some(stamped) = poses.get(index) else {
+                    return;
+                };
+                row.set_selected(*selected == Some(index));
+                let quat = stamped.pose.rotation;
+                let t = stamped.pose.translation.vector;
+                let label = match (&stamped.name, stamped.time) {
+                    (Some(name), _) => name.clone(),
+                    (None, Some(time)) => format!("{time:.6}"),
+                    (None, None) => String::new(),
+                };
+                let cells = [
+                    format!("{index}"),
+                    label,
+                    format!("{:.4}", t.x),
+                    format!("{:.4}", t.y),
+                    format!("{:.4}", t.z),
+                    format!("{:.4}", quat.w),
+                    format!("{:.4}", quat.i),
+                    format!("{:.4}", quat.j),
+                    format!("{:.4}", quat.k),
+                ];
+                for cell in cells {
+                    row.col(|ui| {
+                        ui.label(cell);
+                    });
+                }
+                if row.response().clicked() {
+                    *selected = Some(index);
+                    loaded = Some(stamped.pose);
+                }
+            });
+        });
+    loaded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_formats_agree() {
+        let tum = "# comment\n1.5 1 2 3 0 0 0.7071067811865476 0.7071067811865476\n";
+        let kitti = "0 -1 0 1 1 0 0 2 0 0 1 3\n";
+        let euroc = "#timestamp,p_x,p_y,p_z,q_w,q_x,q_y,q_z\n1500000000,1,2,3,0.7071067811865476,0,0,0.7071067811865476,0.1,0.2\n";
+        // World-to-camera is the inverse: rotate by -90° about Z, translate by -(R^T t).
+        let colmap =
+            "# header\n1 0.7071067811865476 0 0 -0.7071067811865476 -2 1 -3 1 frame 1.png\n\n";
+        let expected = pose_from(
+            [1.0, 2.0, 3.0],
+            na::Quaternion::new(FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2),
+        );
+        for (format, text) in [
+            (TrajectoryFormat::Tum, tum),
+            (TrajectoryFormat::Kitti, kitti),
+            (TrajectoryFormat::Euroc, euroc),
+            (TrajectoryFormat::Colmap, colmap),
+        ] {
+            let poses = parse(format, text).expect("well-formed trajectory");
+            let [stamped] = poses.as_slice() else {
+                panic!("{format:?} has one pose");
+            };
+            assert!(
+                (stamped.pose.to_homogeneous() - expected.to_homogeneous()).norm() < 1e-9,
+                "{format:?} pose"
+            );
+        }
+        assert_eq!(
+            parse(TrajectoryFormat::Colmap, colmap)
+                .expect("valid")
+                .first()
+                .and_then(|stamped| stamped.name.as_deref()),
+            Some("frame 1.png"),
+            "names with spaces"
+        );
+        assert!(
+            parse(TrajectoryFormat::Colmap, "1 1,0 0 0 0 0 0 0 1 a.png\n\n").is_err(),
+            "a comma inside a field is an error, not a panic"
+        );
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let poses = vec![
+            StampedPose {
+                time: Some(0.25),
+                name: None,
+                image_id: None,
+                camera_id: None,
+                extra: None,
+                pose: na::Isometry3::new(
+                    na::Vector3::new(1.0, -2.0, 3.0),
+                    na::Vector3::new(0.1, 0.2, 0.3),
+                ),
+            },
+            StampedPose {
+                time: Some(0.5),
+                name: None,
+                image_id: None,
+                camera_id: None,
+                extra: None,
+                pose: na::Isometry3::new(
+                    na::Vector3::new(0.0, 4.0, -1.0),
+                    na::Vector3::new(-1.0, 0.5, 2.0),
+                ),
+            },
+        ];
+        for format in TrajectoryFormat::iter() {
+            let parsed =
+                parse(format, &super::format(format, &poses)).expect("exported text parses");
+            assert_eq!(parsed.len(), poses.len(), "{format:?} pose count");
+            for (parsed, original) in parsed.iter().zip(&poses) {
+                assert!(
+                    (parsed.pose.to_homogeneous() - original.pose.to_homogeneous()).norm() < 1e-8,
+                    "{format:?} pose roundtrip"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_passthrough_roundtrip() {
+        let colmap = "# IMAGE_ID, QW, QX, QY, QZ, TX, TY, TZ, CAMERA_ID, NAME\n\
+            # POINTS2D[] as (X, Y, POINT3D_ID)\n\
+            7 0.600000000 0.000000000 0.800000000 0.000000000 1.000000000 -2.000000000 3.000000000 2 left/a.png\n\
+            10.5 20.25 -1 3.0 4.0 42\n\
+            12 1.000000000 0.000000000 0.000000000 0.000000000 0.500000000 0.250000000 -4.000000000 3 right/b.png\n";
+        let euroc = format!(
+            "#timestamp [ns],p_RS_R_x [m],p_RS_R_y [m],p_RS_R_z [m],q_RS_w [],q_RS_x [],q_RS_y [],q_RS_z []{EUROC_EXTRA_HEADER}\n\
+            1500000000,1.000000000,-2.000000000,3.000000000,0.600000000,0.000000000,0.800000000,0.000000000,0.1,0.2,0.3,0.01,0.02,0.03,-0.1,-0.2,9.8"
+        );
+        for (format, text) in [
+            (TrajectoryFormat::Colmap, colmap.to_owned()),
+            (TrajectoryFormat::Euroc, euroc),
+        ] {
+            let poses = parse(format, &text).expect("valid trajectory");
+            assert_eq!(super::format(format, &poses), text, "{format:?} export");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Attitude from an accelerometer and a magnetometer reading with the TRIAD method.
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+
+/// Angle in degrees between the two vectors below which TRIAD is reported as ill-conditioned.
+const COLLINEAR_WARNING_DEGREES: f64 = 10.0;
+
+/// Local level reference frame the attitude is expressed in.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum_macros::EnumIter,
+)]
+pub enum ReferenceFrame {
+    /// North, East, Down.
+    Ned,
+    /// East, North, Up.
+    Enu,
+}
+
+impl ReferenceFrame {
+    /// What a resting accelerometer measures (the specific force, pointing up), normalized.
+    pub fn up(self) -> na::Vector3<f64> {
+        match self {
+            Self::Ned => -na::Vector3::z(),
+            Self::Enu => na::Vector3::z(),
+        }
+    }
+
+    /// Horizontal direction of magnetic north, `declination` radians east of true north.
+    pub fn magnetic_north(self, declination: f64) -> na::Vector3<f64> {
+        let (sin, cos) = declination.sin_cos();
+        match self {
+            Self::Ned => na::Vector3::new(cos, sin, 0.0),
+            Self::Enu => na::Vector3::new(sin, cos, 0.0),
+        }
+    }
+}
+
+/// Orthonormal TRIAD frame: the first vector, the normal of both, and their cross product.
+fn triad_frame(
+    first: &na::Vector3<f64>,
+    second: &na::Vector3<f64>,
+) -> anyhow::Result<na::Matrix3<f64>> {
+    let Some(t1) = first.try_normalize(f64::EPSILON) else {
+        anyhow::bail!("vectors must be nonzero");
+    };
+    let Some(t2) = first.cross(second).try_normalize(f64::EPSILON) else {
+        anyhow::bail!("vectors are collinear");
+    };
+    Ok(na::Matrix3::from_columns(&[t1, t2, t1.cross(&t2)]))
+}
+
+/// Rotation `R` with `R * body ≈ reference`, trusting the first (gravity) pair exactly and using
+/// the second (magnetic) pair only to fix the heading.
+///
+/// # Errors
+/// When a vector is zero or a pair is collinear.
+pub fn triad(
+    body_accel: &na::Vector3<f64>,
+    body_mag: &na::Vector3<f64>,
+    reference_accel: &na::Vector3<f64>,
+    reference_mag: &na::Vector3<f64>,
+) -> anyhow::Result<na::UnitQuaternion<f64>> {
+    let body = triad_frame(body_accel, body_mag)?;
+    let reference = triad_frame(reference_accel, reference_mag)?;
+    Ok(na::UnitQuaternion::from_rotation_matrix(
+        &na::Rotation3::from_matrix_unchecked(reference * body.transpose()),
+    ))
+}
+
+/// State of the TRIAD panel.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct TriadTool {
+    body_accel: [(String, String); 3],
+    body_mag: [(String, String); 3],
+    reference_accel: [(String, String); 3],
+    reference_mag: [(String, String); 3],
+    frame: ReferenceFrame,
+    declination_deg: f64,
+}
+
+impl Default for TriadTool {
+    fn default() -> Self {
+        let frame = ReferenceFrame::Ned;
+        Self {
+            body_accel: crate::vector3_fields([0.0, 0.0, -1.0]),
+            body_mag: crate::vector3_fields([1.0, 0.0, 0.0]),
+            reference_accel: crate::vector3_fields(frame.up().into()),
+            reference_mag: crate::vector3_fields(frame.magnetic_north(0.0).into()),
+            frame,
+            declination_deg: 0.0,
+        }
+    }
+}
+
+impl TriadTool {
+    /// Returns the attitude the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::UnitQuaternion<f64>> {
+        ui.label("Body frame readings:");
+        ui.label("accelerometer (specific force, up at rest):");
+        crate::vector3_input(ui, &mut self.body_accel);
+        ui.label("magnetometer:");
+        crate::vector3_input(ui, &mut self.body_mag);
+
+        ui.label("Reference frame vectors:");
+        ui.horizontal(|ui| {
+            let before = (self.frame, self.declination_deg);
+            egui::ComboBox::from_label("frame")
+                .selected_text(format!("{:?}", self.frame))
+                .show_ui(ui, |ui| {
+                    for frame in ReferenceFrame::iter() {
+                        ui.selectable_value(&mut self.frame, frame, format!("{frame:?}"));
+                    }
+                });
+            ui.add(
+                egui::DragValue::new(&mut self.declination_deg)
+                    .range(-180.0..=180.0)
+                    .speed(0.1)
+                    .prefix("declination ")
+                    .suffix("° E"),
+            )
+            .on_hover_text("Refills the reference vectors below when changed");
+            let changed = before != (self.frame, self.declination_deg);
+            if ui.button("fill reference").clicked() || changed {
+                let north = self.frame.magnetic_north(self.declination_deg.to_radians());
+                self.reference_accel = crate::vector3_fields(self.frame.up().into());
+                self.reference_mag = crate::vector3_fields(north.into());
+            }
+        });
+        ui.label("up:");
+        crate::vector3_input(ui, &mut self.reference_accel);
+        ui.label("magnetic field (only its heading is used):");
+        crate::vector3_input(ui, &mut self.reference_mag);
+
+        let vectors = (|| {
+            Ok::<_, anyhow::Error>([
+                crate::parse_vector3(&self.body_accel)?,
+                crate::parse_vector3(&self.body_mag)?,
+                crate::parse_vector3(&self.reference_accel)?,
+                crate::parse_vector3(&self.reference_mag)?,
+            ])
+        })();
+        let [body_accel, body_mag, reference_accel, reference_mag] = match vectors {
+            Ok(vectors) => vectors,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+        for (name, first, second) in [
+            ("body", body_accel, body_mag),
+            ("reference", reference_accel, reference_mag),
+        ] {
+            let angle = first.angle(&second).to_degrees();
+            if angle.min(180.0 - angle) < COLLINEAR_WARNING_DEGREES {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "The {name} vectors are {angle:.2}° apart: nearly collinear, the heading is poorly determined"
+                    ),
+                );
+            }
+        }
+        match triad(&body_accel, &body_mag, &reference_accel, &reference_mag) {
+            Ok(quat) => {
+                let mut loaded = None;
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Body to reference: [{:.4}, {:.4}, {:.4}, {:.4}]",
+                        quat.w, quat.i, quat.j, quat.k
+                    ));
+                    if ui.small_button("load").clicked() {
+                        loaded = Some(quat);
+                    }
+                });
+                loaded
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triad_recovers_attitude() {
+        for frame in ReferenceFrame::iter() {
+            let attitude = na::UnitQuaternion::from_euler_angles(0.2, -0.4, 2.0);
+            let reference_accel = frame.up();
+            // A field with 60° dip; TRIAD only needs its heading.
+            let reference_field = frame.magnetic_north(0.1) * 0.5 - frame.up() * 0.866;
+            let body_accel = attitude.inverse() * reference_accel;
+            let body_mag = attitude.inverse() * reference_field;
+            let solved = triad(
+                &body_accel,
+                &body_mag,
+                &reference_accel,
+                &frame.magnetic_north(0.1),
+            )
+            .expect("well conditioned");
+            assert!(solved.angle_to(&attitude) < 1e-9, "{frame:?} attitude");
+        }
+    }
+}
@@ -0,0 +1,486 @@
+//! Links and joints of a URDF robot description, and forward kinematics between any two links.
+
+use nalgebra as na;
+
+use crate::euler::{EulerFrame, EulerSequence};
+
+/// Range of the value sliders, `±` radians or meters, for joints without `<limit>`.
+const UNLIMITED_TRAVEL: f64 = std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointType {
+    Fixed,
+    Revolute,
+    Continuous,
+    Prismatic,
+    /// Floating and planar joints, which have more than one degree of freedom and are held at
+    /// their origin.
+    Unsupported,
+}
+
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub name: String,
+    pub joint_type: JointType,
+    pub parent: String,
+    pub child: String,
+    /// `<origin>`: pose of the joint frame in the parent link frame.
+    pub origin: na::Isometry3<f64>,
+    /// `<origin rpy>` as written, in radians.
+    pub rpy: [f64; 3],
+    pub axis: na::UnitVector3<f64>,
+    /// `<limit lower upper>`.
+    pub limits: Option<(f64, f64)>,
+}
+
+impl Joint {
+    /// Pose of the child link frame in the parent link frame at joint position `value`.
+    pub fn transform(&self, value: f64) -> na::Isometry3<f64> {
+        let motion = match self.joint_type {
+            JointType::Revolute | JointType::Continuous => na::Isometry3::from_parts(
+                na::Translation3::identity(),
+                na::UnitQuaternion::from_axis_angle(&self.axis, value),
+            ),
+            JointType::Prismatic => na::Isometry3::from_parts(
+                (self.axis.into_inner() * value).into(),
+                na::UnitQuaternion::identity(),
+            ),
+            JointType::Fixed | JointType::Unsupported => na::Isometry3::identity(),
+        };
+        self.origin * motion
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Robot {
+    pub name: String,
+    pub links: Vec<String>,
+    pub joints: Vec<Joint>,
+}
+
+/// URDF `rpy`: roll about the fixed X axis, then pitch about the fixed Y axis, then yaw about the
+/// fixed Z axis, so `R = Rz(yaw) Ry(pitch) Rx(roll)`.
+pub fn rpy_to_quat(rpy: [f64; 3]) -> na::UnitQuaternion<f64> {
+    crate::euler::to_quat(EulerSequence::XYZ, EulerFrame::Extrinsic, rpy)
+}
+
+/// The URDF `rpy` of a rotation, the inverse of [`rpy_to_quat`].
+pub fn quat_to_rpy(quat: &na::UnitQuaternion<f64>) -> [f64; 3] {
+    crate::euler::from_quat(EulerSequence::XYZ, EulerFrame::Extrinsic, quat).angles
+}
+
+/// Three whitespace-separated numbers, `default` when the attribute is missing.
+fn parse_triple(value: Option<&str>, default: [f64; 3]) -> anyhow::Result<[f64; 3]> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    let numbers = value
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<f64>, _>>()?;
+    numbers
+        .try_into()
+        .map_err(|numbers: Vec<f64>| anyhow::anyhow!("expected 3 numbers, got {}", numbers.len()))
+}
+
+fn child_attribute<'a>(node: roxmltree::Node<'a, '_>, tag: &str, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name(tag))?
+        .attribute(name)
+}
+
+fn parse_joint(node: roxmltree::Node<'_, '_>) -> anyhow::Result<Joint> {
+    let name = node.attribute("name").unwrap_or_default().to_owned();
+    let context = |e: anyhow::Error| e.context(format!("joint {name:?}"));
+    let joint_type = match node.attribute("type") {
+        Some("fixed") => JointType::Fixed,
+        Some("revolute") => JointType::Revolute,
+        Some("continuous") => JointType::Continuous,
+        Some("prismatic") => JointType::Prismatic,
+        Some("floating" | "planar") => JointType::Unsupported,
+        other => return Err(context(anyhow::anyhow!("unknown joint type {other:?}"))),
+    };
+    let link = |tag| {
+        child_attribute(node, tag, "link")
+            .map(str::to_owned)
+            .ok_or_else(|| context(anyhow::anyhow!("missing <{tag} link>")))
+    };
+    let xyz = parse_triple(child_attribute(node, "origin", "xyz"), [0.0; 3]).map_err(context)?;
+    let rpy = parse_triple(child_attribute(node, "origin", "rpy"), [0.0; 3]).map_err(context)?;
+    let axis =
+        parse_triple(child_attribute(node, "axis", "xyz"), [1.0, 0.0, 0.0]).map_err(context)?;
+    let Some(axis) = na::UnitVector3::try_new(axis.into(), f64::EPSILON) else {
+        return Err(context(anyhow::anyhow!("zero axis")));
+    };
+    let limit = |bound| child_attribute(node, "limit", bound).and_then(|value| value.parse().ok());
+    Ok(Joint {
+        joint_type,
+        parent: link("parent")?,
+        child: link("child")?,
+        origin: na::Isometry3::from_parts(na::Vector3::from(xyz).into(), rpy_to_quat(rpy)),
+        rpy,
+        axis,
+        limits: limit("lower").zip(limit("upper")),
+        name,
+    })
+}
+
+/// # Errors
+/// When the XML is malformed, the root is not `<robot>`, a joint is malformed or refers to an
+/// unknown link, or a link has two parents.
+pub fn parse(text: &str) -> anyhow::Result<Robot> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    if !root.has_tag_name("robot") {
+        anyhow::bail!(
+            "the root element is <{}>, not <robot>",
+            root.tag_name().name()
+        );
+    }
+    let links = root
+        .children()
+        .filter(|node| node.has_tag_name("link"))
+        .map(|node| node.attribute("name").unwrap_or_default().to_owned())
+        .collect::<Vec<_>>();
+    let joints = root
+        .children()
+        .filter(|node| node.has_tag_name("joint"))
+        .map(parse_joint)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    for joint in &joints {
+        for link in [&joint.parent, &joint.child] {
+            if !links.contains(link) {
+                anyhow::bail!("joint {:?} refers to unknown link {link:?}", joint.name);
+            }
+        }
+        if joints
+            .iter()
+            .filter(|other| other.child == joint.child)
+            .count()
+            > 1
+        {
+            anyhow::bail!("link {:?} has more than one parent joint", joint.child);
+        }
+    }
+    Ok(Robot {
+        name: root.attribute("name").unwrap_or_default().to_owned(),
+        links,
+        joints,
+    })
+}
+
+impl Robot {
+    /// The first link that is no joint's child.
+    pub fn root(&self) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|link| !self.joints.iter().any(|joint| &joint.child == *link))
+            .map(String::as_str)
+    }
+
+    /// Pose of `link` in the frame of the root of its tree, and that root, with `values` indexed
+    /// like `joints`.
+    ///
+    /// # Errors
+    /// When the link is unknown or the joints form a loop.
+    pub fn link_pose<'a>(
+        &'a self,
+        link: &'a str,
+        values: &[f64],
+    ) -> anyhow::Result<(na::Isometry3<f64>, &'a str)> {
+        if !self.links.iter().any(|name| name == link) {
+            anyhow::bail!("unknown link {link:?}");
+        }
+        let mut pose = na::Isometry3::identity();
+        let mut current = link;
+        for _ in 0..=self.joints.len() {
+            let Some((index, joint)) = self
+                .joints
+                .iter()
+                .enumerate()
+                .find(|(_, joint)| joint.child == current)
+            else {
+                return Ok((pose, current));
+            };
+            pose = joint.transform(values.get(index).copied().unwrap_or_default()) * pose;
+            current = &joint.parent;
+        }
+        anyhow::bail!("the joints form a loop")
+    }
+
+    /// Pose of `to` in the frame of `from`.
+    ///
+    /// # Errors
+    /// See [`Self::link_pose`]; also when the links are in different trees.
+    pub fn transform(
+        &self,
+        from: &str,
+        to: &str,
+        values: &[f64],
+    ) -> anyhow::Result<na::Isometry3<f64>> {
+        let (from_pose, from_root) = self.link_pose(from, values)?;
+        let (to_pose, to_root) = self.link_pose(to, values)?;
+        if from_root != to_root {
+            anyhow::bail!("{from:?} and {to:?} are not connected by joints");
+        }
+        Ok(from_pose.inverse() * to_pose)
+    }
+}
+
+fn format_triple(values: [f64; 3]) -> String {
+    let [x, y, z] = values;
+    format!("{x:.6} {y:.6} {z:.6}")
+}
+
+/// State of the URDF panel.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct UrdfTool {
+    text: String,
+    #[serde(skip)]
+    robot: Option<anyhow::Result<Robot>>,
+    /// Joint positions indexed like the robot's joints, radians or meters.
+    values: Vec<f64>,
+    from: String,
+    to: String,
+}
+
+impl UrdfTool {
+    /// Returns the link-to-link transform the user chose to load into the main views.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<na::Isometry3<f64>> {
+        if let Some(dropped) = crate::dropped_text(ui.ctx(), &[".urdf", ".xml"]) {
+            match dropped {
+                Ok(text) => {
+                    self.text = text;
+                    self.robot = None;
+                }
+                Err(e) => self.robot = Some(Err(e)),
+            }
+        }
+        ui.label("Paste a URDF below or drop a .urdf file onto the window.");
+        let edited = ui
+            .add(
+                egui::TextEdit::multiline(&mut self.text)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(6),
+            )
+            .changed();
+        if edited || self.text.trim().is_empty() {
+            self.robot = None;
+        }
+        if self.robot.is_none() && !self.text.trim().is_empty() {
+            self.robot = Some(parse(&self.text));
+        }
+        let Some(robot) = &self.robot else {
+            return None;
+        };
+        let robot = match robot {
+            Ok(robot) => robot.clone(),
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{e:#}"));
+                return None;
+            }
+        };
+        ui.label(format!(
+            "Robot {:?}: {} links, {} joints",
+            robot.name,
+            robot.links.len(),
+            robot.joints.len()
+        ));
+        ui.label(
+            "URDF rpy is fixed-axis roll (X), then pitch (Y), then yaw (Z), all about the parent \
+             frame: R = Rz(yaw) Ry(pitch) Rx(roll). In the Euler view this is XYZ Extrinsic, or \
+             ZYX Intrinsic with the angles listed as yaw, pitch, roll.",
+        );
+        self.values.resize(robot.joints.len(), 0.0);
+        if !robot.links.contains(&self.from) {
+            self.from = robot.root().unwrap_or_default().to_owned();
+        }
+        if !robot.links.contains(&self.to) {
+            self.to = robot.links.last().cloned().unwrap_or_default();
+        }
+        self.joints_ui(ui, &robot);
+        self.transform_ui(ui, &robot)
+    }
+
+    fn joints_ui(&mut self, ui: &mut egui::Ui, robot: &Robot) {
+        egui::Grid::new("urdf_joints").striped(true).show(ui, |ui| {
+            for header in [
+                "joint",
+                "type",
+                "parent → child",
+                "origin xyz",
+                "origin rpy (deg)",
+                "axis",
+                "value",
+            ] {
+                ui.label(egui::RichText::new(header).strong());
+            }
+            ui.end_row();
+            for (joint, value) in robot.joints.iter().zip(&mut self.values) {
+                ui.label(&joint.name);
+                ui.label(format!("{:?}", joint.joint_type));
+                ui.label(format!("{} → {}", joint.parent, joint.child));
+                ui.label(format_triple(joint.origin.translation.vector.into()));
+                ui.label(format_triple(joint.rpy.map(f64::to_degrees)));
+                ui.label(format_triple(joint.axis.into_inner().into()));
+                let (lower, upper) = joint
+                    .limits
+                    .unwrap_or((-UNLIMITED_TRAVEL, UNLIMITED_TRAVEL));
+                match joint.joint_type {
+                    JointType::Revolute | JointType::Continuous => {
+                        let mut degrees = value.to_degrees();
+                        if ui
+                            .add(
+                                egui::Slider::new(
+                                    &mut degrees,
+                                    lower.to_degrees()..=upper.to_degrees(),
+                                )
+                                .suffix("°"),
+                            )
+                            .changed()
+                        {
+                            *value = degrees.to_radians();
+                        }
+                    }
+                    JointType::Prismatic => {
+                        ui.add(egui::Slider::new(value, lower..=upper).suffix(" m"));
+                    }
+                    JointType::Fixed | JointType::Unsupported => {
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn transform_ui(&mut self, ui: &mut egui::Ui, robot: &Robot) -> Option<na::Isometry3<f64>> {
+        ui.horizontal(|ui| {
+            for (label, link) in [("from", &mut self.from), ("to", &mut self.to)] {
+                egui::ComboBox::from_label(label)
+                    .selected_text(link.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in &robot.links {
+                            ui.selectable_value(link, name.clone(), name);
+                        }
+                    });
+            }
+        });
+        let transform = match robot.transform(&self.from, &self.to, &self.values) {
+            Ok(transform) => transform,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+        let (rotation, translation) = (transform.rotation, transform.translation.vector);
+        let rpy = quat_to_rpy(&rotation);
+        ui.label(format!(
+            "Pose of {:?} in the frame of {:?}:",
+            self.to, self.from
+        ));
+        ui.label(format!(
+            "quaternion [{:.4}, {:.4}, {:.4}, {:.4}], translation [{:.4}, {:.4}, {:.4}]",
+            rotation.w,
+            rotation.i,
+            rotation.j,
+            rotation.k,
+            translation.x,
+            translation.y,
+            translation.z
+        ));
+        let [roll, pitch, yaw] = rpy.map(f64::to_degrees);
+        ui.label(format!("roll {roll:.4}°, pitch {pitch:.4}°, yaw {yaw:.4}°"));
+        let origin = format!(
+            "<origin xyz=\"{}\" rpy=\"{}\"/>",
+            format_triple(translation.into()),
+            format_triple(rpy)
+        );
+        let mut loaded = None;
+        ui.horizontal(|ui| {
+            ui.monospace(&origin);
+            if ui.small_button("copy").clicked() {
+                ui.ctx().copy_text(origin.clone());
+            }
+            if ui.small_button("load").clicked() {
+                loaded = Some(transform);
+            }
+        });
+        loaded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARM: &str = r#"
+        <robot name="arm">
+          <link name="base"/>
+          <link name="upper"/>
+          <link name="tool"/>
+          <joint name="shoulder" type="revolute">
+            <parent link="base"/>
+            <child link="upper"/>
+            <origin xyz="0 0 1" rpy="0 0 1.5707963267948966"/>
+            <axis xyz="0 1 0"/>
+            <limit lower="-1" upper="1"/>
+          </joint>
+          <joint name="wrist" type="fixed">
+            <parent link="upper"/>
+            <child link="tool"/>
+            <origin xyz="0.5 0 0"/>
+          </joint>
+        </robot>
+    "#;
+
+    #[test]
+    fn test_rpy_is_fixed_axis_xyz() {
+        let (roll, pitch, yaw) = (0.3, -0.5, 1.2);
+        let expected = na::UnitQuaternion::from_axis_angle(&na::Vector3::z_axis(), yaw)
+            * na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), pitch)
+            * na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), roll);
+        let quat = rpy_to_quat([roll, pitch, yaw]);
+        assert!(quat.angle_to(&expected) < 1e-12, "Rz Ry Rx");
+        let [r, p, y] = quat_to_rpy(&quat);
+        assert!(
+            (na::Vector3::new(r, p, y) - na::Vector3::new(roll, pitch, yaw)).norm() < 1e-12,
+            "rpy roundtrip"
+        );
+    }
+
+    #[test]
+    fn test_forward_kinematics() {
+        let robot = parse(ARM).expect("valid URDF");
+        assert_eq!(robot.joints.len(), 2, "two joints");
+        assert_eq!(robot.root(), Some("base"), "root link");
+        // At zero the upper link is yawed 90°, so the tool sits 0.5 along base +Y.
+        let tool = robot
+            .transform("base", "tool", &[0.0, 0.0])
+            .expect("known links");
+        assert!(
+            (tool.translation.vector - na::Vector3::new(0.0, 0.5, 1.0)).norm() < 1e-12,
+            "tool position at zero"
+        );
+        // Pitching about the upper link's Y axis by 90° swings the tool straight down.
+        let bent = robot
+            .transform("base", "tool", &[std::f64::consts::FRAC_PI_2, 0.0])
+            .expect("known links");
+        assert!(
+            (bent.translation.vector - na::Vector3::new(0.0, 0.0, 0.5)).norm() < 1e-12,
+            "tool position when bent"
+        );
+        let back = robot
+            .transform("tool", "base", &[0.3, 0.0])
+            .expect("known links");
+        let forward = robot
+            .transform("base", "tool", &[0.3, 0.0])
+            .expect("known links");
+        assert!(
+            (back * forward).translation.vector.norm() < 1e-12,
+            "inverse direction"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! The JavaScript API of the web build, on `window.rottool` once the app has started.
+//!
+//! ```js
+//! rottool.convert("0 0 0.7071 0.7071", "QuaternionXYZW", "RowMajor3x3");
+//! rottool.convertValues([0.1, 0.2, 0.3], "Euler", "QuaternionWXYZ", "ZYX", "Intrinsic");
+//! rottool.setRotation("0 0 1.5708", "RotationVector");
+//! rottool.getRotation("RollPitchYaw");
+//! ```
+//!
+//! `index.html` forwards `{id, method, args}` messages from an embedding page to these
+//! functions and posts `{id, result}` or `{id, error}` back, for the page's own origin, the
+//! embedding parent's and those listed in its `data-allowed-origins`.
+
+use std::cell::RefCell;
+
+use nalgebra as na;
+use strum::IntoEnumIterator as _;
+use wasm_bindgen::prelude::*;
+
+use crate::euler::{EulerFrame, EulerSequence};
+use crate::raw::RawStringType;
+use crate::repr::{Representation, by_name};
+
+/// A rotation, and a translation for layouts that carry one.
+pub type Pose = (na::UnitQuaternion<f64>, Option<na::Vector3<f64>>);
+
+/// Shared between the app and JavaScript; the web build runs on a single thread.
+#[derive(Default)]
+struct Bridge {
+    /// Woken when JavaScript sets a pose, so it shows without waiting for input.
+    ctx: Option<egui::Context>,
+    /// The app's pose as of the last frame.
+    current: na::Isometry3<f64>,
+    /// A pose set from JavaScript that the app has not picked up yet.
+    pending: Option<Pose>,
+}
+
+thread_local! {
+    static BRIDGE: RefCell<Bridge> = RefCell::default();
+}
+
+fn js_error(e: &anyhow::Error) -> JsError {
+    JsError::new(&format!("{e:#}"))
+}
+
+fn parse(input: &str, layout: &str) -> Result<Pose, JsError> {
+    let layout = by_name::<RawStringType>(layout).map_err(|e| js_error(&e))?;
+    crate::repr::parse_raw(input, layout).map_err(|e| js_error(&e))
+}
+
+fn format(
+    quat: &na::UnitQuaternion<f64>,
+    translation: &na::Vector3<f64>,
+    layout: &str,
+) -> Result<String, JsError> {
+    let layout = by_name::<RawStringType>(layout).map_err(|e| js_error(&e))?;
+    Ok(layout.format_transform(quat, translation))
+}
+
+/// Takes the pose set from JavaScript since the last frame, if any.
+pub fn take_pending(ctx: &egui::Context) -> Option<Pose> {
+    BRIDGE.with_borrow_mut(|bridge| {
+        bridge.ctx.get_or_insert_with(|| ctx.clone());
+        bridge.pending.take()
+    })
+}
+
+/// Records the app's pose for [`get_rotation`].
+pub fn publish(transform: na::Isometry3<f64>) {
+    BRIDGE.with_borrow_mut(|bridge| bridge.current = transform);
+}
+
+/// Converts raw text between two layouts of `layouts()`, keeping the translation.
+///
+/// # Errors
+/// When a layout name is unknown or the text does not parse.
+#[wasm_bindgen]
+pub fn convert(input: &str, from_layout: &str, to_layout: &str) -> Result<String, JsError> {
+    let (quat, translation) = parse(input, from_layout)?;
+    format(&quat, &translation.unwrap_or_default(), to_layout)
+}
+
+/// Converts numbers between two representations of `representations()`. Euler angles are in
+/// radians, by default intrinsic ZYX.
+///
+/// # Errors
+/// When a name is unknown or the numbers are not a rotation.
+#[wasm_bindgen(js_name = convertValues)]
+pub fn convert_values(
+    values: &[f64],
+    from_repr: &str,
+    to_repr: &str,
+    sequence: Option<String>,
+    frame: Option<String>,
+) -> Result<Vec<f64>, JsError> {
+    let from = by_name::<Representation>(from_repr).map_err(|e| js_error(&e))?;
+    let to = by_name::<Representation>(to_repr).map_err(|e| js_error(&e))?;
+    let sequence = sequence
+        .map_or(Ok(EulerSequence::ZYX), |name| by_name(&name))
+        .map_err(|e| js_error(&e))?;
+    let frame = frame
+        .map_or(Ok(EulerFrame::Intrinsic), |name| by_name(&name))
+        .map_err(|e| js_error(&e))?;
+    let quat = from
+        .quat(values, sequence, frame)
+        .map_err(|e| js_error(&e))?;
+    Ok(to.values(&quat, sequence, frame))
+}
+
+/// The app's current rotation and translation as text, by default as a row-major 4x4 matrix.
+///
+/// # Errors
+/// When the layout name is unknown.
+#[wasm_bindgen(js_name = getRotation)]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "wasm-bindgen passes optional strings by value"
+)]
+pub fn get_rotation(layout: Option<String>) -> Result<String, JsError> {
+    let current = BRIDGE.with_borrow(|bridge| bridge.current);
+    format(
+        &current.rotation,
+        &current.translation.vector,
+        layout.as_deref().unwrap_or("RowMajor4x4"),
+    )
+}
+
+/// Makes the rotation in `input` the app's current one, with its translation for layouts that
+/// carry one.
+///
+/// # Errors
+/// When the layout name is unknown or the text does not parse.
+#[wasm_bindgen(js_name = setRotation)]
+pub fn set_rotation(input: &str, layout: &str) -> Result<(), JsError> {
+    let pose = parse(input, layout)?;
+    BRIDGE.with_borrow_mut(|bridge| {
+        bridge.pending = Some(pose);
+        if let Some(ctx) = &bridge.ctx {
+            ctx.request_repaint();
+        }
+    });
+    Ok(())
+}
+
+/// Names accepted by `convert`, `getRotation` and `setRotation`.
+#[wasm_bindgen]
+pub fn layouts() -> Vec<String> {
+    RawStringType::iter()
+        .map(|layout| format!("{layout:?}"))
+        .collect()
+}
+
+/// Names accepted by `convertValues`.
+#[wasm_bindgen]
+pub fn representations() -> Vec<String> {
+    Representation::iter()
+        .map(|repr| format!("{repr:?}"))
+        .collect()
+}